@@ -54,11 +54,24 @@ window.handleTaskAnnotations = (event: KeyboardEvent | MouseEvent) => {
     return false;
 };
 
+window.handleTaskAnnotationEdit = (shortkey: string) => {
+    let editor = document.getElementById("anno_edt_inp_" + shortkey);
+    if (editor) {
+        document.getElementById("anno_txt_" + shortkey)?.classList.add('hidden');
+        editor.classList.remove('hidden');
+        editor.focus();
+    }
+}
+
 window.handleTaskAnnotationTrigger = (event: KeyboardEvent | MouseEvent) => {
     event.preventDefault();
     if (event.target) {
         let shortkey = event.target.value;
-        if (shortkey.length >= 2) { 
+        if (shortkey.startsWith("/")) {
+            if (shortkey.length >= 3) {
+                window.handleTaskAnnotationEdit(shortkey.substring(1));
+            }
+        } else if (shortkey.length >= 2) { 
             let element = document.getElementById("anno_dlt_" + shortkey);
             if (element) {
                 element.click();
@@ -144,6 +157,48 @@ document.addEventListener('click', function (event) {
     return;
 })
 
+// While the task database is locked, e.g. by the Taskwarrior CLI,
+// the server answers with 503 and a `Retry-After` header.
+const MAX_BUSY_RETRIES = 3;
+
+document.addEventListener('htmx:responseError', function (event: any) {
+    const xhr: XMLHttpRequest = event.detail.xhr;
+    const retryAfter = xhr.getResponseHeader('Retry-After');
+    if (xhr.status !== 503 || retryAfter === null) {
+        return;
+    }
+    const elt: HTMLElement = event.detail.elt;
+    const retries = parseInt(elt.dataset.busyRetries ?? "0");
+    if (retries >= MAX_BUSY_RETRIES) {
+        delete elt.dataset.busyRetries;
+        return;
+    }
+    elt.dataset.busyRetries = (retries + 1).toString();
+    const config = event.detail.requestConfig;
+    setTimeout(() => {
+        // @ts-ignore
+        window.htmx.ajax(config.verb, config.path, {
+            source: elt,
+            target: event.detail.target,
+            values: config.parameters,
+        });
+    }, parseInt(retryAfter) * 1000);
+});
+
+document.addEventListener('htmx:afterRequest', function (event: any) {
+    if (event.detail.successful) {
+        delete event.detail.elt.dataset.busyRetries;
+    }
+});
+
+// Changes of the task database, also by the Taskwarrior CLI or other tabs,
+// are re-triggered on the body, where the views listen for them.
+const taskEvents = new EventSource("events");
+taskEvents.addEventListener("tasks-changed", function () {
+    // @ts-ignore
+    window.htmx.trigger(document.body, "tasks-changed");
+});
+
 document.addEventListener("DOMContentLoaded", function () {
     theme.init();
 
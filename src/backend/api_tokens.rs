@@ -0,0 +1,164 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Personal API tokens for scripts, stored in `api_tokens.json` next to the configuration.
+//!
+//! Tokens are sent as `Authorization: Bearer <token>`. Only the SHA-256 hash of a token
+//! is stored, the token itself is shown once after it was created.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::http::Method;
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::distr::{Alphanumeric, SampleString};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use taskchampion::Uuid;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use super::sync_server::write_atomic;
+
+/// Start of every token, makes them easy to spot in scripts and secret scanners.
+pub const TOKEN_PREFIX: &str = "twk_";
+
+/// The last use is written at most once per interval, not on every request.
+const LAST_USED_PRECISION: TimeDelta = TimeDelta::minutes(1);
+
+/// What a token may do.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenScope {
+    /// Only requests which change nothing, like `GET`.
+    ReadOnly,
+    ReadWrite,
+}
+
+impl TokenScope {
+    pub fn allows(self, method: &Method) -> bool {
+        self == Self::ReadWrite || method.is_safe()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    pub scope: TokenScope,
+    /// Hex encoded SHA-256 hash of the token.
+    pub hash: String,
+    pub created: DateTime<Utc>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// API tokens in a JSON file, shared by all requests.
+#[derive(Clone)]
+pub struct ApiTokenStore {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl ApiTokenStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    async fn read(&self) -> Result<Vec<ApiToken>, anyhow::Error> {
+        match fs::read(&self.path).await {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write(&self, tokens: &[ApiToken]) -> Result<(), anyhow::Error> {
+        write_atomic(&self.path, &serde_json::to_vec_pretty(tokens)?).await
+    }
+
+    /// All tokens, in the order they were created.
+    pub async fn list(&self) -> Result<Vec<ApiToken>, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        self.read().await
+    }
+
+    /// Creates a token, returns it together with the token to hand out,
+    /// which cannot be read again later.
+    pub async fn create(
+        &self,
+        name: &str,
+        scope: TokenScope,
+    ) -> Result<(ApiToken, String), anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let secret = format!(
+            "{TOKEN_PREFIX}{}",
+            Alphanumeric.sample_string(&mut rand::rng(), 40)
+        );
+        let token = ApiToken {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            scope,
+            hash: hash_token(&secret),
+            created: Utc::now(),
+            last_used: None,
+        };
+        let mut tokens = self.read().await?;
+        tokens.push(token.clone());
+        self.write(&tokens).await?;
+        Ok((token, secret))
+    }
+
+    /// Removes the token, `false` if there was none with the id.
+    pub async fn revoke(&self, id: Uuid) -> Result<bool, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let mut tokens = self.read().await?;
+        let count = tokens.len();
+        tokens.retain(|p| p.id != id);
+        if tokens.len() == count {
+            return Ok(false);
+        }
+        self.write(&tokens).await?;
+        Ok(true)
+    }
+
+    /// Looks up the token sent by a client and records its use.
+    pub async fn verify(&self, secret: &str) -> Result<Option<ApiToken>, anyhow::Error> {
+        if !secret.starts_with(TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let hash = hash_token(secret);
+        let _guard = self.lock.lock().await;
+        let mut tokens = self.read().await?;
+        let Some(token) = tokens.iter_mut().find(|p| p.hash == hash) else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        if token
+            .last_used
+            .is_none_or(|p| now - p >= LAST_USED_PRECISION)
+        {
+            token.last_used = Some(now);
+            let token = token.clone();
+            self.write(&tokens).await?;
+            return Ok(Some(token));
+        }
+        Ok(Some(token.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,54 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use axum::http::Method;
+
+use super::{ApiTokenStore, TOKEN_PREFIX, TokenScope};
+
+#[tokio::test]
+async fn test_api_tokens() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("api_tokens.json");
+    let store = ApiTokenStore::new(path.clone());
+    assert!(store.list().await?.is_empty());
+
+    let (backup, secret) = store.create("backup", TokenScope::ReadOnly).await?;
+    let (_, other_secret) = store.create("lights", TokenScope::ReadWrite).await?;
+    assert!(secret.starts_with(TOKEN_PREFIX));
+    assert_ne!(secret, other_secret);
+    // only the hash is stored.
+    let content = std::fs::read_to_string(&path)?;
+    assert!(!content.contains(&secret));
+    assert!(content.contains(&backup.hash));
+
+    let used = store.verify(&secret).await?.unwrap();
+    assert_eq!(used.id, backup.id);
+    assert!(used.last_used.is_some());
+    let tokens = store.list().await?;
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].last_used, used.last_used);
+    assert!(tokens[1].last_used.is_none());
+    assert!(store.verify("twk_unknown").await?.is_none());
+    assert!(store.verify(&secret[TOKEN_PREFIX.len()..]).await?.is_none());
+
+    assert!(store.revoke(backup.id).await?);
+    assert!(!store.revoke(backup.id).await?);
+    assert!(store.verify(&secret).await?.is_none());
+    assert!(store.verify(&other_secret).await?.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_token_scope() {
+    assert!(TokenScope::ReadOnly.allows(&Method::GET));
+    assert!(!TokenScope::ReadOnly.allows(&Method::POST));
+    assert!(!TokenScope::ReadOnly.allows(&Method::PATCH));
+    assert!(TokenScope::ReadWrite.allows(&Method::DELETE));
+}
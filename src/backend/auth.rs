@@ -0,0 +1,103 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Passwords and sessions of the users in the `[auth]` settings.
+//!
+//! A logged in user gets a session cookie signed with the session key,
+//! holding the user and the end of the session.
+
+use std::sync::LazyLock;
+
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use axum_extra::extract::cookie::Key;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha512};
+
+use crate::core::config::AuthSettings;
+
+/// Name of the cookie holding the session.
+pub const SESSION_COOKIE: &str = "twk_session";
+
+/// Compared against for unknown users, so they take as long as a wrong password.
+static UNKNOWN_USER_HASH: LazyLock<String> =
+    LazyLock::new(|| hash_password("unknown user").unwrap_or_default());
+
+/// Hashes the password with argon2 for the `password_hash` of a user.
+pub fn hash_password(password: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|p| p.to_string())
+        .map_err(|e| anyhow::anyhow!("Cannot hash the password: {e}"))
+}
+
+/// Checks the password of the user, `false` for unknown users.
+pub fn verify_password(settings: &AuthSettings, username: &str, password: &str) -> bool {
+    let user = settings.user(username);
+    let hash = user.map_or(UNKNOWN_USER_HASH.as_str(), |p| p.password_hash.as_str());
+    let valid = PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    });
+    valid && user.is_some()
+}
+
+/// Key the session cookies are signed with, random without a `session_secret`.
+pub fn session_key(settings: &AuthSettings) -> Key {
+    settings
+        .session_secret
+        .as_ref()
+        .map_or_else(Key::generate, |secret| {
+            Key::from(&Sha512::digest(secret.as_bytes()))
+        })
+}
+
+/// Session of a logged in user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub username: String,
+    pub expires: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn new(username: &str, settings: &AuthSettings) -> Self {
+        Self {
+            username: username.to_string(),
+            expires: Utc::now()
+                .checked_add_signed(settings.session_duration())
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+
+    /// Value of the session cookie, the signature is added by the cookie jar.
+    pub fn cookie_value(&self) -> String {
+        format!("{}:{}", self.expires.timestamp(), self.username)
+    }
+
+    /// Reads the session from the cookie value,
+    /// `None` if the session ended or the user was removed from the settings.
+    pub fn from_cookie_value(value: &str, settings: &AuthSettings) -> Option<Self> {
+        let (expires, username) = value.split_once(':')?;
+        let expires = DateTime::from_timestamp(expires.parse().ok()?, 0)?;
+        if expires <= Utc::now() || settings.user(username).is_none() {
+            return None;
+        }
+        Some(Self {
+            username: username.to_string(),
+            expires,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use chrono::{Duration, Utc};
+
+use super::{Session, hash_password, session_key, verify_password};
+use crate::core::config::{AuthSettings, AuthUser};
+
+fn settings() -> AuthSettings {
+    AuthSettings {
+        users: vec![AuthUser {
+            username: String::from("alex"),
+            password_hash: hash_password("correct horse").unwrap(),
+        }],
+        ..AuthSettings::default()
+    }
+}
+
+#[test]
+fn test_verify_password() {
+    let settings = settings();
+    assert!(settings.users[0].password_hash.starts_with("$argon2id$"));
+    assert!(verify_password(&settings, "alex", "correct horse"));
+    assert!(!verify_password(&settings, "alex", "battery staple"));
+    assert!(!verify_password(&settings, "sam", "correct horse"));
+}
+
+#[test]
+fn test_session_cookie_value() {
+    let settings = settings();
+    let session = Session::new("alex", &settings);
+    assert!(session.expires > Utc::now() + Duration::hours(24 * 7 - 1));
+    let read = Session::from_cookie_value(&session.cookie_value(), &settings).unwrap();
+    assert_eq!(read.username, "alex");
+    assert_eq!(read.expires.timestamp(), session.expires.timestamp());
+
+    let expired = Session {
+        expires: Utc::now() - Duration::minutes(1),
+        ..session.clone()
+    };
+    assert!(Session::from_cookie_value(&expired.cookie_value(), &settings).is_none());
+    // removed from the settings.
+    assert!(
+        Session::from_cookie_value(&session.cookie_value(), &AuthSettings::default()).is_none()
+    );
+    assert!(Session::from_cookie_value("alex", &settings).is_none());
+}
+
+#[test]
+fn test_session_key() {
+    let settings = AuthSettings {
+        session_secret: Some(String::from("kept across restarts")),
+        ..AuthSettings::default()
+    };
+    assert_eq!(
+        session_key(&settings).master(),
+        session_key(&settings).master()
+    );
+    assert_ne!(
+        session_key(&AuthSettings::default()).master(),
+        session_key(&AuthSettings::default()).master()
+    );
+}
@@ -0,0 +1,585 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Taskwarrior hook scripts.
+//!
+//! Hooks run before the changes are committed, following the hook protocol of Taskwarrior:
+//! - `on-add` hooks get the new task as JSON line on stdin,
+//!   `on-modify` hooks get the original and the modified task.
+//! - A hook prints the task, possibly changed, as a single JSON line. All other lines
+//!   are feedback for the user.
+//! - A non-zero exit status rejects the change, the feedback tells why.
+//!
+//! Hooks of the same event run in the order of their file names,
+//! each one gets the task as printed by the previous one.
+//!
+//! `on-launch` hooks run once when the server starts, `on-exit` hooks after every
+//! request which changed tasks, with the changed tasks as JSON lines on stdin.
+//! Both only print feedback.
+//!
+//! Hooks run as child processes without blocking the server, a hook still running
+//! after `hooks.timeout` seconds is killed.
+//!
+//! The most recent invocations are kept in the [`HookLog`] of the `AppState`.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskchampion::{Operations, Tag, Uuid};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+use super::task::Task;
+use crate::core::app::AppState;
+
+#[cfg(windows)]
+const LINE_ENDING: &str = "\r\n";
+#[cfg(not(windows))]
+const LINE_ENDING: &str = "\n";
+
+/// Supported hook events based on taskwarrior definitions.
+///
+/// `OnAdd` requires executable script named with starting `on-add` and is executed
+/// on new definition of a task.
+/// `OnModify` requires executable script named with starting `on-modify` and is executed
+/// whenever a task is changed.
+/// `OnLaunch` requires executable script named with starting `on-launch` and is executed
+/// when the server starts.
+/// `OnExit` requires executable script named with starting `on-exit` and is executed
+/// after the changes of a request were saved.
+#[derive(Clone, Debug)]
+pub enum TaskEvent {
+    OnAdd,
+    OnModify,
+    OnLaunch,
+    OnExit,
+}
+
+impl Display for TaskEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::OnAdd => "on-add".to_string(),
+            Self::OnModify => "on-modify".to_string(),
+            Self::OnLaunch => "on-launch".to_string(),
+            Self::OnExit => "on-exit".to_string(),
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Number of hook invocations kept in the [`HookLog`].
+const MAX_HOOK_LOG_ENTRIES: usize = 100;
+
+/// A single invocation of a hook.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookLogEntry {
+    pub time: DateTime<Utc>,
+    pub hook: String,
+    pub event: String,
+    /// Tasks given to the hook.
+    pub tasks: Vec<Uuid>,
+    /// Exit code, missing if the hook was killed or could not be started.
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Default)]
+struct HookLogInner {
+    entries: VecDeque<HookLogEntry>,
+    failures: u64,
+}
+
+/// The most recent hook invocations, shared through the `AppState`.
+#[derive(Clone, Default)]
+pub struct HookLog {
+    inner: Arc<Mutex<HookLogInner>>,
+}
+
+impl HookLog {
+    pub fn record(&self, entry: HookLogEntry) {
+        if let Ok(mut inner) = self.inner.lock() {
+            if !entry.success {
+                inner.failures += 1;
+            }
+            if inner.entries.len() >= MAX_HOOK_LOG_ENTRIES {
+                inner.entries.pop_front();
+            }
+            inner.entries.push_back(entry);
+        }
+    }
+
+    /// The logged invocations, the most recent first.
+    pub fn entries(&self) -> Vec<HookLogEntry> {
+        self.inner
+            .lock()
+            .map(|p| p.entries.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of failed invocations since the start.
+    pub fn failures(&self) -> u64 {
+        self.inner.lock().map(|p| p.failures).unwrap_or_default()
+    }
+}
+
+tokio::task_local! {
+    /// Failed invocations of the action run by [`track_hook_failures`].
+    static ACTION_HOOK_FAILURES: Cell<u64>;
+}
+
+/// Runs the action, telling whether one of the hooks it ran failed.
+///
+/// The failures are counted per action, the hooks of concurrent requests
+/// are not taken into account.
+pub async fn track_hook_failures<F: Future>(action: F) -> (F::Output, bool) {
+    ACTION_HOOK_FAILURES
+        .scope(Cell::new(0), async move {
+            let output = action.await;
+            (output, ACTION_HOOK_FAILURES.with(Cell::get) > 0)
+        })
+        .await
+}
+
+/// The task as accepted by all hooks, with the feedback they printed.
+#[derive(Debug, Clone)]
+pub struct HookOutcome {
+    pub task: Task,
+    pub feedback: Vec<String>,
+}
+
+/// A hook rejected the change by exiting with a non-zero status.
+#[derive(Debug, Clone)]
+pub struct HookRejection {
+    pub hook: String,
+    pub feedback: Vec<String>,
+}
+
+impl Display for HookRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.feedback.is_empty() {
+            write!(f, "Hook {} rejected the change", self.hook)
+        } else {
+            write!(
+                f,
+                "Hook {} rejected the change: {}",
+                self.hook,
+                self.feedback.join(" ")
+            )
+        }
+    }
+}
+
+impl std::error::Error for HookRejection {}
+
+/// Finds the executable hook scripts of the event, ordered by their name.
+///
+/// # Notes
+///
+/// - This function uses Unix-style file permission checks to determine
+///   whether a file is executable.
+/// - Hook scripts must conform to the naming conventions (beginning with the
+///   task event type) to be picked up for execution.
+pub fn find_hooks(hooks_dir: &Option<PathBuf>, event_type: &TaskEvent) -> Vec<PathBuf> {
+    let Some(hooks_dir) = hooks_dir.as_ref() else {
+        debug!("Hooks are disabled, skipping hook execution.");
+        return vec![];
+    };
+
+    let Ok(paths) = std::fs::read_dir(hooks_dir) else {
+        warn!(
+            "Failed to read Hooks directory {:?}, skipping hook execution.",
+            hooks_dir
+        );
+        return vec![];
+    };
+    let mut hooks: Vec<PathBuf> = paths
+        .filter_map(|dir_entry| {
+            let entry = dir_entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let is_executable = meta.permissions().mode() & 0o111 != 0;
+            if !meta.is_file() || !is_executable {
+                return None;
+            }
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(&event_type.to_string()))
+                .then(|| entry.path())
+        })
+        .collect();
+    hooks.sort();
+    hooks
+}
+
+fn hook_name(hook: &Path) -> String {
+    hook.file_name().map_or_else(
+        || hook.display().to_string(),
+        |p| p.to_string_lossy().to_string(),
+    )
+}
+
+/// What a hook printed, split into task JSON lines and feedback.
+struct HookOutput {
+    success: bool,
+    tasks: Vec<String>,
+    feedback: Vec<String>,
+}
+
+async fn spawn_hook(
+    hook: &Path,
+    name: &str,
+    input: &str,
+    timeout: Duration,
+) -> Result<Output, anyhow::Error> {
+    debug!("Hook {} will be executed with stdin: {}", name, input);
+    let mut child = Command::new(hook)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // the hook is killed when it runs out of time.
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Hook {name} failed to spawn: {e}"))?;
+    let run = async {
+        if let Some(mut stdin) = child.stdin.take() {
+            // hooks are free to ignore their input.
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+        child.wait_with_output().await
+    };
+    let Ok(output) = tokio::time::timeout(timeout, run).await else {
+        bail!(
+            "Hook {name} did not finish within {} seconds and was killed",
+            timeout.as_secs()
+        );
+    };
+    Ok(output?)
+}
+
+/// Runs the hook and records the invocation in the [`HookLog`].
+async fn execute_hook(
+    app_state: &AppState,
+    hook: &Path,
+    event_type: &TaskEvent,
+    tasks: Vec<Uuid>,
+    input: &str,
+) -> Result<HookOutput, anyhow::Error> {
+    let name = hook_name(hook);
+    let time = Utc::now();
+    let started = Instant::now();
+    let result = spawn_hook(hook, &name, input, app_state.app_config.hooks.timeout()).await;
+    let (exit_code, success, stdout, stderr) = match &result {
+        Ok(output) => (
+            output.status.code(),
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ),
+        Err(e) => (None, false, String::new(), e.to_string()),
+    };
+    if !success {
+        // outside of a tracked action there is nobody to tell.
+        let _ = ACTION_HOOK_FAILURES.try_with(|p| p.set(p.get() + 1));
+    }
+    app_state.hook_log.record(HookLogEntry {
+        time,
+        hook: name.clone(),
+        event: event_type.to_string(),
+        tasks,
+        exit_code,
+        success,
+        duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        stdout: stdout.clone(),
+        stderr: stderr.clone(),
+    });
+    let output = result?;
+    info!("Hook {} called, exit status: {}", name, output.status);
+    debug!("Hook {} output was {:?}", name, stdout);
+    if !stderr.trim().is_empty() {
+        warn!("Hook {} printed errors: {}", name, stderr.trim());
+    }
+
+    let (tasks, mut feedback): (Vec<String>, Vec<String>) = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(ToString::to_string)
+        .partition(|p| p.starts_with('{'));
+    if !success && feedback.is_empty() {
+        // a failing hook without feedback most likely printed why to stderr.
+        feedback = stderr
+            .lines()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(ToString::to_string)
+            .collect();
+    }
+    Ok(HookOutput {
+        success,
+        tasks,
+        feedback,
+    })
+}
+
+/// Runs the hooks of the event on the task, before it is committed.
+///
+/// `old` is the task before the change, it is only given to `on-modify` hooks.
+///
+/// # Errors
+///
+/// Returns a [`HookRejection`] if a hook rejected the change, and an error
+/// if a hook could not be run or printed something else than a task.
+pub async fn run_hooks(
+    app_state: &AppState,
+    event_type: &TaskEvent,
+    old: Option<&Task>,
+    new: &Task,
+) -> Result<HookOutcome, anyhow::Error> {
+    let mut outcome = HookOutcome {
+        task: new.clone(),
+        feedback: vec![],
+    };
+    for hook in find_hooks(&app_state.task_hooks_path, event_type) {
+        let name = hook_name(&hook);
+        let mut input = String::new();
+        if matches!(event_type, TaskEvent::OnModify) {
+            input.push_str(&serde_json::to_string(&old.unwrap_or(new))?);
+            input.push_str(LINE_ENDING);
+        }
+        input.push_str(&serde_json::to_string(&outcome.task)?);
+        input.push_str(LINE_ENDING);
+
+        let HookOutput {
+            success,
+            tasks,
+            feedback,
+        } = execute_hook(app_state, &hook, event_type, vec![new.uuid], &input).await?;
+        if !success {
+            return Err(HookRejection {
+                hook: name,
+                feedback,
+            }
+            .into());
+        }
+        match tasks.as_slice() {
+            // hooks only used for their side effects keep the task as it is.
+            [] => {}
+            [task] => {
+                let task: Task = serde_json::from_str(task)
+                    .map_err(|e| anyhow::anyhow!("Hook {name} printed an invalid task: {e}"))?;
+                if task.uuid != new.uuid {
+                    bail!("Hook {name} must not change the uuid of the task");
+                }
+                outcome.task = task;
+            }
+            _ => bail!("Hook {name} printed {} tasks instead of one", tasks.len()),
+        }
+        outcome.feedback.extend(feedback);
+    }
+    for feedback in &outcome.feedback {
+        info!("Hook feedback: {}", feedback);
+    }
+    Ok(outcome)
+}
+
+/// Runs the `on-launch` or `on-exit` hooks, which get the given tasks and only print feedback.
+///
+/// # Errors
+///
+/// Returns a [`HookRejection`] if a hook exited with a non-zero status, and an error
+/// if a hook could not be run or printed a task.
+pub async fn run_event_hooks(
+    app_state: &AppState,
+    event_type: &TaskEvent,
+    tasks: &[Task],
+) -> Result<Vec<String>, anyhow::Error> {
+    let mut input = String::new();
+    for task in tasks {
+        input.push_str(&serde_json::to_string(task)?);
+        input.push_str(LINE_ENDING);
+    }
+    let uuids: Vec<Uuid> = tasks.iter().map(|p| p.uuid).collect();
+    let mut feedback = vec![];
+    for hook in find_hooks(&app_state.task_hooks_path, event_type) {
+        let name = hook_name(&hook);
+        let output = execute_hook(app_state, &hook, event_type, uuids.clone(), &input).await?;
+        if !output.success {
+            return Err(HookRejection {
+                hook: name,
+                feedback: output.feedback,
+            }
+            .into());
+        }
+        if !output.tasks.is_empty() {
+            bail!("Hook {name} must not print tasks for {event_type}");
+        }
+        feedback.extend(output.feedback);
+    }
+    for feedback in &feedback {
+        info!("Hook feedback: {}", feedback);
+    }
+    Ok(feedback)
+}
+
+/// Runs the `on-exit` hooks with the tasks changed by a request.
+/// The changes are saved already, so failing hooks are only logged.
+pub async fn run_exit_hooks(app_state: &AppState, tasks: &[Task]) {
+    if let Err(e) = run_event_hooks(app_state, &TaskEvent::OnExit, tasks).await {
+        warn!("{}", e);
+    }
+}
+
+/// Runs the hooks of the event on a changed task, before its operations are committed.
+/// Changes of the hooks are applied to the task and pushed to `ops`.
+///
+/// # Errors
+///
+/// Returns a [`HookRejection`] if a hook rejected the change, see [`run_hooks`].
+pub async fn run_hooks_on_task(
+    app_state: &AppState,
+    event_type: &TaskEvent,
+    old: Option<&taskchampion::Task>,
+    task: &mut taskchampion::Task,
+    ops: &mut Operations,
+) -> Result<Vec<String>, anyhow::Error> {
+    let old: Option<Task> = old.map(|p| p.clone().into());
+    let new: Task = task.clone().into();
+    let outcome = run_hooks(app_state, event_type, old.as_ref(), &new).await?;
+    apply_hook_changes(task, &new, &outcome.task, ops)?;
+    Ok(outcome.feedback)
+}
+
+/// Applies the changes a hook made to the task to the pending operations.
+///
+/// `before` is the task as given to the hooks and `after` the task they returned.
+/// Only properties Taskwarrior users edit are considered, computed ones
+/// like `id`, `urgency` or `modified` are ignored.
+///
+/// # Errors
+///
+/// Will return error if a changed property cannot be set on the task
+pub fn apply_hook_changes(
+    task: &mut taskchampion::Task,
+    before: &Task,
+    after: &Task,
+    ops: &mut Operations,
+) -> Result<(), anyhow::Error> {
+    // compare to what the hooks read, so the JSON round trip itself is no change.
+    let before: Task = serde_json::from_str(&serde_json::to_string(before)?)?;
+
+    if before.description != after.description {
+        task.set_description(after.description.clone(), ops)?;
+    }
+    if before.status != after.status
+        && let Some(status) = &after.status
+    {
+        task.set_status(status.clone(), ops)?;
+    }
+    if before.project != after.project {
+        task.set_value("project", after.project.clone(), ops)?;
+    }
+    if before.priority != after.priority {
+        task.set_value(
+            "priority",
+            after.priority.clone().filter(|p| !p.is_empty()),
+            ops,
+        )?;
+    }
+    if before.recur != after.recur {
+        task.set_value("recur", after.recur.clone(), ops)?;
+    }
+    for (property, old_value, new_value) in [
+        ("entry", before.entry, after.entry),
+        ("start", before.start, after.start),
+        ("end", before.end, after.end),
+        ("due", before.due, after.due),
+        ("wait", before.wait, after.wait),
+        ("scheduled", before.scheduled, after.scheduled),
+        ("until", before.until, after.until),
+    ] {
+        if old_value != new_value {
+            task.set_timestamp(property, new_value, ops)?;
+        }
+    }
+
+    let old_tags: HashSet<String> = before.tags.unwrap_or_default().into_iter().collect();
+    let new_tags: HashSet<String> = after.tags.clone().unwrap_or_default().into_iter().collect();
+    for tag in old_tags.difference(&new_tags) {
+        task.remove_tag(&Tag::from_str(tag)?, ops)?;
+    }
+    for tag in new_tags.difference(&old_tags) {
+        task.add_tag(&Tag::from_str(tag)?, ops)?;
+    }
+
+    let old_deps: HashSet<_> = before.depends.unwrap_or_default().into_iter().collect();
+    let new_deps: HashSet<_> = after
+        .depends
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    for dep in old_deps.difference(&new_deps) {
+        task.remove_dependency(*dep, ops)?;
+    }
+    for dep in new_deps.difference(&old_deps) {
+        task.add_dependency(*dep, ops)?;
+    }
+
+    let old_annotations = before.annotations.unwrap_or_default();
+    let new_annotations = after.annotations.clone().unwrap_or_default();
+    for annotation in &old_annotations {
+        if !new_annotations
+            .iter()
+            .any(|p| p.entry() == annotation.entry())
+        {
+            task.remove_annotation(*annotation.entry(), ops)?;
+        }
+    }
+    for annotation in &new_annotations {
+        if !old_annotations.contains(annotation) {
+            task.add_annotation(
+                taskchampion::Annotation {
+                    entry: *annotation.entry(),
+                    description: annotation.description().to_string(),
+                },
+                ops,
+            )?;
+        }
+    }
+
+    let old_udas: HashMap<String, String> = before.uda.unwrap_or_default();
+    let new_udas: HashMap<String, String> = after.uda.clone().unwrap_or_default();
+    for key in old_udas.keys().filter(|p| !new_udas.contains_key(*p)) {
+        task.remove_user_defined_attribute(key, ops)?;
+    }
+    for (key, value) in &new_udas {
+        if old_udas.get(key) != Some(value) {
+            task.set_user_defined_attribute(key, value, ops)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,394 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use taskchampion::{Operations, Status, Uuid};
+
+use super::{
+    HookLog, HookLogEntry, MAX_HOOK_LOG_ENTRIES, TaskEvent, apply_hook_changes, run_event_hooks,
+    run_hooks,
+};
+use crate::NewTask;
+use crate::backend::task::{Task, get_task, get_tasks};
+use crate::core::config::{AppSettings, HookSettings};
+use crate::endpoints::tasks::{run_modify_command, task_add, task_undo, toggle_task_active};
+use crate::get_random_appstate;
+
+fn write_hook(hooks_dir: &Path, name: &str, script: &str) -> anyhow::Result<()> {
+    fs::create_dir_all(hooks_dir)?;
+    let hook_file = hooks_dir.join(name);
+    fs::write(&hook_file, format!("#!/bin/bash\n{script}\n"))?;
+    let mut perms = fs::metadata(&hook_file)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&hook_file, perms)?;
+    Ok(())
+}
+
+fn new_task(description: &str) -> NewTask {
+    NewTask::new(Some(description.into()), None, None, None, None)
+}
+
+#[tokio::test]
+async fn test_hooks_change_added_task() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    // hooks are chained in the order of their names.
+    write_hook(
+        &hooks_dir,
+        "on-add.02-reviewed",
+        r#"tail -n 1 | sed 's/"description":"\([^"]*\)"/"description":"\1, reviewed"/'
+echo "Reviewed the task""#,
+    )?;
+    write_hook(
+        &hooks_dir,
+        "on-add.01-drafted",
+        r#"tail -n 1 | sed 's/"description":"\([^"]*\)"/"description":"\1, drafted"/'"#,
+    )?;
+
+    let uuid = task_add(&new_task("Write the report"), &app_state)
+        .await
+        .unwrap();
+    let task = get_task(&app_state.replica, uuid).await?.unwrap();
+    assert_eq!(task.description, "Write the report, drafted, reviewed");
+    assert_eq!(task.status, Some(Status::Pending));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_reject_added_task() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    write_hook(
+        &hooks_dir,
+        "on-add.no-reports",
+        "echo \"Reports are written on fridays\"\nexit 1",
+    )?;
+
+    let result = task_add(&new_task("Write the report"), &app_state)
+        .await
+        .unwrap_err();
+    assert!(!result.is_success());
+    let msg = result.msg.unwrap();
+    assert!(msg.contains("on-add.no-reports"));
+    assert!(msg.contains("Reports are written on fridays"));
+    assert!(get_tasks(&app_state.replica).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_reject_modified_task() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let uuid = task_add(&new_task("Call Alice"), &app_state).await.unwrap();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    // the original and the modified task are given to on-modify hooks.
+    write_hook(
+        &hooks_dir,
+        "on-modify.frozen",
+        "[ \"$(wc -l)\" -eq 2 ] || exit 2\necho \"Calls are frozen\"\nexit 1",
+    )?;
+
+    let result = run_modify_command(uuid, "priority:H", &app_state)
+        .await
+        .unwrap_err();
+    assert!(result.msg.unwrap().contains("Calls are frozen"));
+    let task = get_task(&app_state.replica, uuid).await?.unwrap();
+    assert_ne!(task.priority, Some("H".into()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_invalid_output() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    let task: Task = serde_json::from_str(&format!(
+        r#"{{"uuid":"{}","description":"Water the plants","status":"pending"}}"#,
+        Uuid::new_v4()
+    ))?;
+
+    write_hook(&hooks_dir, "on-add.broken", r#"echo '{"description": 3}'"#)?;
+    assert!(
+        run_hooks(&app_state, &TaskEvent::OnAdd, None, &task)
+            .await
+            .is_err()
+    );
+
+    write_hook(
+        &hooks_dir,
+        "on-add.broken",
+        "read -r task\necho \"$task\"\necho \"$task\"",
+    )?;
+    assert!(
+        run_hooks(&app_state, &TaskEvent::OnAdd, None, &task)
+            .await
+            .is_err()
+    );
+
+    write_hook(&hooks_dir, "on-add.broken", "read -r task\necho \"$task\"")?;
+    let outcome = run_hooks(&app_state, &TaskEvent::OnAdd, None, &task).await?;
+    assert_eq!(outcome.task.description, "Water the plants");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_apply_hook_changes() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let uuid = task_add(
+        &NewTask::new(
+            Some("Plan the sprint".into()),
+            Some("+work".into()),
+            Some("Team".into()),
+            None,
+            Some("due:2025-10-01".into()),
+        ),
+        &app_state,
+    )
+    .await
+    .unwrap();
+    let mut replica = app_state.replica.write().await?;
+    let mut task = replica.get_task(uuid).await?.unwrap();
+    let before: Task = task.clone().into();
+
+    // an unchanged task leaves nothing to commit.
+    let mut ops = Operations::new();
+    apply_hook_changes(&mut task, &before, &before, &mut ops)?;
+    assert!(ops.is_empty());
+
+    let after = Task {
+        description: "Plan the next sprint".into(),
+        project: None,
+        tags: Some(vec!["review".into()]),
+        priority: Some("M".into()),
+        due: None,
+        ..before.clone()
+    };
+    apply_hook_changes(&mut task, &before, &after, &mut ops)?;
+    replica.commit_operations(ops).await?;
+    drop(replica);
+
+    let task = get_task(&app_state.replica, uuid).await?.unwrap();
+    assert_eq!(task.description, "Plan the next sprint");
+    assert_eq!(task.project, None);
+    assert_eq!(task.priority, Some("M".into()));
+    assert_eq!(task.due, None);
+    assert!(
+        task.tags
+            .unwrap_or_default()
+            .contains(&"review".to_string())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_launch_hooks() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    write_hook(&hooks_dir, "on-launch.greet", "echo \"Good morning\"")?;
+    let feedback = run_event_hooks(&app_state, &TaskEvent::OnLaunch, &[]).await?;
+    assert_eq!(feedback, vec!["Good morning".to_string()]);
+
+    write_hook(&hooks_dir, "on-launch.greet", "echo \"Not today\"\nexit 1")?;
+    let err = run_event_hooks(&app_state, &TaskEvent::OnLaunch, &[])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Not today"));
+
+    // on-launch and on-exit hooks only give feedback.
+    write_hook(
+        &hooks_dir,
+        "on-launch.greet",
+        r#"echo '{"description": "Task"}'"#,
+    )?;
+    assert!(
+        run_event_hooks(&app_state, &TaskEvent::OnLaunch, &[])
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_exit_hooks_get_changed_tasks() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    let log_file = hooks_dir.join("exit.log");
+    write_hook(
+        &hooks_dir,
+        "on-exit.log",
+        &format!(
+            "cat >> \"{}\"\necho \"Backed up\"\nexit 1",
+            log_file.display()
+        ),
+    )?;
+
+    // a failing on-exit hook does not undo the saved changes.
+    let first = task_add(&new_task("Book the flight"), &app_state)
+        .await
+        .unwrap();
+    let second = task_add(&new_task("Book the hotel"), &app_state)
+        .await
+        .unwrap();
+    toggle_task_active(first, "start".into(), &app_state).await?;
+    toggle_task_active(second, "start".into(), &app_state).await?;
+    run_modify_command(second, "priority:H", &app_state)
+        .await
+        .unwrap();
+    assert_eq!(task_undo(1, &app_state).await?, 1);
+
+    let log: Vec<Task> = fs::read_to_string(&log_file)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let uuids: Vec<Uuid> = log.iter().map(|p| p.uuid).collect();
+    // added, added, started, stopped and started, modified, reverted.
+    assert_eq!(
+        uuids,
+        vec![first, second, first, first, second, second, second]
+    );
+    assert!(log[2].start.is_some());
+    assert!(log[3].start.is_none());
+    assert_eq!(log[5].priority, Some("H".into()));
+    assert_ne!(log[6].priority, Some("H".into()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_modify_hooks_run_once_on_restarted_task() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    let log_file = hooks_dir.join("modify.log");
+    let first = task_add(&new_task("Book the flight"), &app_state)
+        .await
+        .unwrap();
+    let second = task_add(&new_task("Book the hotel"), &app_state)
+        .await
+        .unwrap();
+    toggle_task_active(first, "start".into(), &app_state).await?;
+    write_hook(
+        &hooks_dir,
+        "on-modify.log",
+        &format!("tail -n 1 | tee -a \"{}\"", log_file.display()),
+    )?;
+
+    // the other active task is stopped, the started one is restarted.
+    toggle_task_active(second, "start".into(), &app_state).await?;
+    toggle_task_active(second, "start".into(), &app_state).await?;
+
+    let log: Vec<Task> = fs::read_to_string(&log_file)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let uuids: Vec<Uuid> = log.iter().map(|p| p.uuid).collect();
+    assert_eq!(uuids, vec![first, second, second]);
+    assert!(log[0].start.is_none());
+    assert!(log[2].start.is_some());
+    assert!(
+        get_task(&app_state.replica, second)
+            .await?
+            .unwrap()
+            .start
+            .is_some()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_timeout() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = get_random_appstate();
+    app_state.app_config = std::sync::Arc::new(AppSettings {
+        hooks: HookSettings {
+            timeout: 1,
+            ..HookSettings::default()
+        },
+        ..AppSettings::default()
+    });
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    let pid_file = hooks_dir.join("hung.pid");
+    write_hook(
+        &hooks_dir,
+        "on-add.hung",
+        &format!("echo $$ > \"{}\"\nsleep 30", pid_file.display()),
+    )?;
+
+    let started = std::time::Instant::now();
+    let result = task_add(&new_task("Wait for it"), &app_state)
+        .await
+        .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    assert!(result.msg.unwrap().contains("was killed"));
+    assert!(get_tasks(&app_state.replica).await?.is_empty());
+    // the hung hook does not linger.
+    let pid = fs::read_to_string(&pid_file)?;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!Path::new(&format!("/proc/{}", pid.trim())).exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_stderr_feedback() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    write_hook(
+        &hooks_dir,
+        "on-add.missing",
+        "echo \"timew: not found\" >&2\nexit 127",
+    )?;
+
+    let result = task_add(&new_task("Track the time"), &app_state)
+        .await
+        .unwrap_err();
+    assert!(result.msg.unwrap().contains("timew: not found"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hook_log() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    write_hook(
+        &hooks_dir,
+        "on-add.accept",
+        "read -r task\necho \"$task\"\necho \"Accepted\"",
+    )?;
+    write_hook(&hooks_dir, "on-exit.fail", "echo \"Disk full\" >&2\nexit 4")?;
+
+    let uuid = task_add(&new_task("Renew the passport"), &app_state)
+        .await
+        .unwrap();
+    let entries = app_state.hook_log.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(app_state.hook_log.failures(), 1);
+    // the most recent invocation comes first.
+    assert_eq!(entries[0].hook, "on-exit.fail");
+    assert_eq!(entries[0].event, "on-exit");
+    assert_eq!(entries[0].exit_code, Some(4));
+    assert!(!entries[0].success);
+    assert_eq!(entries[0].stderr.trim(), "Disk full");
+    assert_eq!(entries[0].tasks, vec![uuid]);
+    assert_eq!(entries[1].hook, "on-add.accept");
+    assert_eq!(entries[1].event, "on-add");
+    assert!(entries[1].success);
+    assert!(entries[1].stdout.contains("Accepted"));
+    assert_eq!(entries[1].tasks, vec![uuid]);
+
+    // only the most recent invocations are kept.
+    let log = HookLog::default();
+    for _ in 0..=MAX_HOOK_LOG_ENTRIES {
+        log.record(HookLogEntry {
+            hook: "on-launch.other".into(),
+            ..entries[0].clone()
+        });
+    }
+    assert_eq!(log.entries().len(), MAX_HOOK_LOG_ENTRIES);
+    assert_eq!(log.failures(), MAX_HOOK_LOG_ENTRIES as u64 + 1);
+    Ok(())
+}
@@ -0,0 +1,254 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Long-lived replicas shared by all requests.
+//!
+//! Opening a replica opens the SQLite database and checks its schema,
+//! so the replicas are kept open for the lifetime of the application.
+//! Reads are served by a small pool of read-only replicas, which do not wait
+//! for the write lock. Writes go through a single replica one at a time.
+//! The undo history is read through a read-only storage kept open the same way,
+//! as the replica does not expose the unsynchronized operations.
+//!
+//! A replica caches the dependency map of the tasks, which is only refreshed
+//! by its own changes. Changes of other replicas, the Taskwarrior CLI or a
+//! synchronization are detected through the database files, and the cache
+//! is rebuilt before the replica is handed out again.
+
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::SystemTime;
+
+use taskchampion::storage::{AccessMode, Storage};
+use taskchampion::{Operations, Replica, SqliteStorage};
+use tokio::sync::{Mutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+
+use super::task::{get_read_only_replica, get_replica};
+
+/// Number of replicas serving reads at the same time.
+const MAX_READERS: usize = 4;
+/// Files of the taskchampion database, the write-ahead log changes on every commit.
+pub(crate) const DATABASE_FILES: [&str; 2] = ["taskchampion.sqlite3", "taskchampion.sqlite3-wal"];
+
+/// State of the database as seen by a replica.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+    generation: u64,
+    files: Vec<Option<(SystemTime, u64)>>,
+}
+
+struct PooledReplica {
+    replica: Replica<SqliteStorage>,
+    fingerprint: Option<Fingerprint>,
+}
+
+struct PoolInner {
+    taskdb: PathBuf,
+    /// Bumped whenever the writer is released.
+    generation: AtomicU64,
+    writer: Arc<Mutex<Option<PooledReplica>>>,
+    readers: StdMutex<Vec<PooledReplica>>,
+    read_permits: Arc<Semaphore>,
+    /// Read-only storage for the undo history, opened on first use.
+    history: Mutex<Option<SqliteStorage>>,
+}
+
+/// Replicas of the task database, shared through the `AppState`.
+#[derive(Clone)]
+pub struct ReplicaPool {
+    inner: Arc<PoolInner>,
+}
+
+impl ReplicaPool {
+    /// Creates the pool for the database in `taskdb`.
+    /// Replicas are opened on first use.
+    pub fn new(taskdb: PathBuf) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                taskdb,
+                generation: AtomicU64::new(0),
+                writer: Arc::new(Mutex::new(None)),
+                readers: StdMutex::new(vec![]),
+                read_permits: Arc::new(Semaphore::new(MAX_READERS)),
+                history: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn taskdb(&self) -> &Path {
+        &self.inner.taskdb
+    }
+
+    /// Hands out a replica for reading, waiting if all readers are busy.
+    ///
+    /// # Errors
+    ///
+    /// Will return error if the replica cannot be opened
+    pub async fn read(&self) -> Result<ReplicaReadGuard, anyhow::Error> {
+        let permit = self.inner.read_permits.clone().acquire_owned().await?;
+        let pooled = self.inner.readers.lock().ok().and_then(|mut p| p.pop());
+        let pooled = self.inner.refresh(pooled, AccessMode::ReadOnly).await?;
+        Ok(ReplicaReadGuard {
+            pooled: Some(pooled),
+            pool: self.inner.clone(),
+            _permit: permit,
+        })
+    }
+
+    /// Lists the local operations which were not synchronized yet, oldest first.
+    /// Like reads, it does not wait for the write lock.
+    ///
+    /// # Errors
+    ///
+    /// Will return error if the storage cannot be opened or read
+    pub async fn unsynced_operations(&self) -> Result<Operations, anyhow::Error> {
+        let _permit = self.inner.read_permits.acquire().await?;
+        let mut history = self.inner.history.lock().await;
+        let storage = match history.take() {
+            Some(storage) => history.insert(storage),
+            None => history.insert(
+                SqliteStorage::new(self.inner.taskdb.clone(), AccessMode::ReadOnly, true).await?,
+            ),
+        };
+        Ok(storage.txn().await?.unsynced_operations().await?)
+    }
+
+    /// Hands out the replica for changes, waiting for the current writer to finish.
+    ///
+    /// # Errors
+    ///
+    /// Will return error if the replica cannot be opened
+    pub async fn write(&self) -> Result<ReplicaWriteGuard, anyhow::Error> {
+        let mut guard = self.inner.writer.clone().lock_owned().await;
+        let pooled = self
+            .inner
+            .refresh(guard.take(), AccessMode::ReadWrite)
+            .await?;
+        *guard = Some(pooled);
+        Ok(ReplicaWriteGuard {
+            guard,
+            pool: self.inner.clone(),
+        })
+    }
+}
+
+impl PoolInner {
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint {
+            generation: self.generation.load(Ordering::Acquire),
+            files: DATABASE_FILES
+                .iter()
+                .map(|p| {
+                    std::fs::metadata(self.taskdb.join(p))
+                        .ok()
+                        .and_then(|m| Some((m.modified().ok()?, m.len())))
+                })
+                .collect(),
+        }
+    }
+
+    /// Opens a replica if needed and rebuilds its caches if the database changed
+    /// since it was used last.
+    async fn refresh(
+        &self,
+        pooled: Option<PooledReplica>,
+        access_mode: AccessMode,
+    ) -> Result<PooledReplica, anyhow::Error> {
+        // Taken before the refresh, so changes made meanwhile are caught next time.
+        let fingerprint = self.fingerprint();
+        match pooled {
+            Some(mut pooled) => {
+                if pooled.fingerprint.as_ref() != Some(&fingerprint) {
+                    pooled.replica.dependency_map(true).await?;
+                    pooled.fingerprint = Some(fingerprint);
+                }
+                Ok(pooled)
+            }
+            None => Ok(PooledReplica {
+                replica: match access_mode {
+                    AccessMode::ReadOnly => get_read_only_replica(&self.taskdb).await?,
+                    AccessMode::ReadWrite => get_replica(&self.taskdb).await?,
+                },
+                fingerprint: Some(fingerprint),
+            }),
+        }
+    }
+}
+
+/// Replica used for reading, it returns to the pool when dropped.
+pub struct ReplicaReadGuard {
+    pooled: Option<PooledReplica>,
+    pool: Arc<PoolInner>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for ReplicaReadGuard {
+    type Target = Replica<SqliteStorage>;
+
+    fn deref(&self) -> &Self::Target {
+        &self
+            .pooled
+            .as_ref()
+            .expect("replica is checked out")
+            .replica
+    }
+}
+
+impl DerefMut for ReplicaReadGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self
+            .pooled
+            .as_mut()
+            .expect("replica is checked out")
+            .replica
+    }
+}
+
+impl Drop for ReplicaReadGuard {
+    fn drop(&mut self) {
+        if let Some(pooled) = self.pooled.take()
+            && let Ok(mut readers) = self.pool.readers.lock()
+        {
+            readers.push(pooled);
+        }
+    }
+}
+
+/// The single replica used for changes, other writers wait until it is dropped.
+pub struct ReplicaWriteGuard {
+    guard: OwnedMutexGuard<Option<PooledReplica>>,
+    pool: Arc<PoolInner>,
+}
+
+impl Deref for ReplicaWriteGuard {
+    type Target = Replica<SqliteStorage>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard.as_ref().expect("replica is checked out").replica
+    }
+}
+
+impl DerefMut for ReplicaWriteGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard.as_mut().expect("replica is checked out").replica
+    }
+}
+
+impl Drop for ReplicaWriteGuard {
+    fn drop(&mut self) {
+        // All replicas, including the writer, catch up with the changes on next use.
+        self.pool.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests;
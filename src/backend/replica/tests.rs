@@ -0,0 +1,104 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use taskchampion::{Operations, Status, Uuid};
+
+use super::{MAX_READERS, ReplicaPool};
+use crate::backend::task::get_replica;
+
+async fn add_task(pool: &ReplicaPool, description: &str) -> anyhow::Result<Uuid> {
+    let uuid = Uuid::new_v4();
+    let mut replica = pool.write().await?;
+    let mut ops = Operations::new();
+    let mut task = replica.create_task(uuid, &mut ops).await?;
+    task.set_description(description.into(), &mut ops)?;
+    task.set_status(Status::Pending, &mut ops)?;
+    replica.commit_operations(ops).await?;
+    Ok(uuid)
+}
+
+#[tokio::test]
+async fn test_replica_pool_reads_writes() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let pool = ReplicaPool::new(tmp_dir.path().to_path_buf());
+    let uuid = add_task(&pool, "Pooled task").await?;
+
+    let mut readers = vec![];
+    for _ in 0..MAX_READERS {
+        readers.push(pool.read().await?);
+    }
+    for reader in &mut readers {
+        let task = reader.get_task(uuid).await?.unwrap();
+        assert_eq!(task.get_description(), "Pooled task");
+    }
+    drop(readers);
+    assert_eq!(pool.inner.readers.lock().unwrap().len(), MAX_READERS);
+
+    // readers are reused and see changes of the writer
+    let second = add_task(&pool, "Another task").await?;
+    let mut replica = pool.read().await?;
+    assert!(replica.get_task(second).await?.is_some());
+    drop(replica);
+    assert_eq!(pool.inner.readers.lock().unwrap().len(), MAX_READERS);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replica_pool_external_changes() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let pool = ReplicaPool::new(tmp_dir.path().to_path_buf());
+    let blocking = add_task(&pool, "Blocking task").await?;
+    let blocked = add_task(&pool, "Blocked task").await?;
+    {
+        let mut replica = pool.read().await?;
+        let task = replica.get_task(blocking).await?.unwrap();
+        assert!(!task.is_blocking());
+    }
+
+    // the dependency is added outside the pool, like the Taskwarrior CLI would
+    let mut external = get_replica(tmp_dir.path()).await?;
+    let mut ops = Operations::new();
+    let mut task = external.get_task(blocked).await?.unwrap();
+    task.add_dependency(blocking, &mut ops)?;
+    external.commit_operations(ops).await?;
+
+    let mut replica = pool.read().await?;
+    let task = replica.get_task(blocking).await?.unwrap();
+    assert!(task.is_blocking());
+    drop(replica);
+    let mut replica = pool.write().await?;
+    let task = replica.get_task(blocked).await?.unwrap();
+    assert!(task.is_blocked());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replica_pool_reads_while_locked() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let pool = ReplicaPool::new(tmp_dir.path().to_path_buf());
+    let uuid = add_task(&pool, "Locked task").await?;
+
+    // like the Taskwarrior CLI in the middle of a change
+    let con = rusqlite::Connection::open(tmp_dir.path().join("taskchampion.sqlite3"))?;
+    con.execute_batch("BEGIN IMMEDIATE")?;
+    let started = std::time::Instant::now();
+    let mut replica = pool.read().await?;
+    assert!(replica.get_task(uuid).await?.is_some());
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    assert!(crate::backend::task::get_undo_history(&pool).await.is_ok());
+
+    // the read-only replica refuses changes
+    let mut ops = Operations::new();
+    let mut task = replica.get_task(uuid).await?.unwrap();
+    task.set_description("Changed".into(), &mut ops)?;
+    assert!(replica.commit_operations(ops).await.is_err());
+    con.execute_batch("ROLLBACK")?;
+    Ok(())
+}
@@ -0,0 +1,284 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use taskchampion::server::{
+    AddVersionResult, GetVersionResult, HistorySegment, Snapshot, SnapshotUrgency, VersionId,
+};
+use taskchampion::{Server, ServerConfig};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use super::replica::ReplicaPool;
+use crate::core::app::AppState;
+use crate::core::config::SyncSettings;
+
+/// Upper bound of the delay between failed background synchronizations.
+const MAX_SYNC_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// Outcome of a synchronization with the sync server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SyncReport {
+    /// Versions of other replicas which were applied locally.
+    pub pulled: usize,
+    /// Versions with local changes which were sent to the server.
+    pub pushed: usize,
+}
+
+/// Wraps the sync server, counts the versions exchanged with it
+/// and fails requests which are not answered within `timeout`.
+struct CountingServer {
+    inner: Box<dyn Server>,
+    timeout: Duration,
+    pulled: Rc<Cell<usize>>,
+    pushed: Rc<Cell<usize>>,
+}
+
+/// Fails the `request` if the sync server does not answer within `timeout`.
+async fn with_timeout<T>(
+    timeout: Duration,
+    request: impl Future<Output = Result<T, taskchampion::Error>>,
+) -> Result<T, taskchampion::Error> {
+    tokio::time::timeout(timeout, request).await.map_err(|_| {
+        taskchampion::Error::Server(format!("No answer from the sync server within {timeout:?}"))
+    })?
+}
+
+#[async_trait(?Send)]
+impl Server for CountingServer {
+    async fn add_version(
+        &mut self,
+        parent_version_id: VersionId,
+        history_segment: HistorySegment,
+    ) -> Result<(AddVersionResult, SnapshotUrgency), taskchampion::Error> {
+        let result = with_timeout(
+            self.timeout,
+            self.inner.add_version(parent_version_id, history_segment),
+        )
+        .await?;
+        if let (AddVersionResult::Ok(_), _) = result {
+            self.pushed.set(self.pushed.get() + 1);
+        }
+        Ok(result)
+    }
+
+    async fn get_child_version(
+        &mut self,
+        parent_version_id: VersionId,
+    ) -> Result<GetVersionResult, taskchampion::Error> {
+        let result = with_timeout(
+            self.timeout,
+            self.inner.get_child_version(parent_version_id),
+        )
+        .await?;
+        if let GetVersionResult::Version { .. } = result {
+            self.pulled.set(self.pulled.get() + 1);
+        }
+        Ok(result)
+    }
+
+    async fn add_snapshot(
+        &mut self,
+        version_id: VersionId,
+        snapshot: Snapshot,
+    ) -> Result<(), taskchampion::Error> {
+        with_timeout(self.timeout, self.inner.add_snapshot(version_id, snapshot)).await
+    }
+
+    async fn get_snapshot(&mut self) -> Result<Option<(VersionId, Snapshot)>, taskchampion::Error> {
+        with_timeout(self.timeout, self.inner.get_snapshot()).await
+    }
+}
+
+/// Taskchampion exchanges the versions with the server inside a single database
+/// transaction, so the replica is locked for writes during the whole round-trip.
+/// Changes wait for the synchronization to finish, which is why every request
+/// to the server is bounded by `timeout`.
+async fn sync_with_server(
+    replicas: ReplicaPool,
+    server_config: ServerConfig,
+    avoid_snapshots: bool,
+    timeout: Duration,
+) -> Result<SyncReport, anyhow::Error> {
+    let pulled = Rc::new(Cell::new(0));
+    let pushed = Rc::new(Cell::new(0));
+    let mut server: Box<dyn Server> = Box::new(CountingServer {
+        inner: with_timeout(timeout, server_config.into_server()).await?,
+        timeout,
+        pulled: pulled.clone(),
+        pushed: pushed.clone(),
+    });
+    let mut replica = replicas.write().await?;
+    replica.sync(&mut server, avoid_snapshots).await?;
+    Ok(SyncReport {
+        pulled: pulled.get(),
+        pushed: pushed.get(),
+    })
+}
+
+/// Synchronizes the replica with the configured sync server.
+///
+/// The sync servers of taskchampion cannot be sent between threads,
+/// so the synchronization is driven on a blocking thread of the runtime.
+pub async fn sync_replica(
+    replicas: &ReplicaPool,
+    settings: &SyncSettings,
+) -> Result<SyncReport, anyhow::Error> {
+    let server_config = settings.server_config()?;
+    let replicas = replicas.clone();
+    let avoid_snapshots = settings.avoid_snapshots;
+    let timeout = settings.request_timeout();
+    let handle = tokio::runtime::Handle::current();
+    let report = tokio::task::spawn_blocking(move || {
+        handle.block_on(sync_with_server(
+            replicas,
+            server_config,
+            avoid_snapshots,
+            timeout,
+        ))
+    })
+    .await??;
+    info!(
+        "Synchronized replica, pulled {} and pushed {} versions",
+        report.pulled, report.pushed
+    );
+    Ok(report)
+}
+
+/// Health of the synchronization, as shown in the UI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SyncStatus {
+    pub last_attempt: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_report: Option<SyncReport>,
+    /// Error of the last attempt, cleared by the next successful synchronization.
+    pub last_error: Option<String>,
+    /// Number of failed attempts since the last successful synchronization.
+    pub failures: u32,
+}
+
+/// Synchronization state shared between the requests and the background scheduler.
+/// Only one synchronization runs at a time.
+#[derive(Clone, Default)]
+pub struct SyncState {
+    status: Arc<RwLock<SyncStatus>>,
+    running: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl SyncState {
+    pub fn status(&self) -> SyncStatus {
+        self.status.read().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.try_lock().is_err()
+    }
+
+    /// Synchronizes the replica, waiting for a running synchronization to finish first.
+    pub async fn sync(
+        &self,
+        replicas: &ReplicaPool,
+        settings: &SyncSettings,
+    ) -> Result<SyncReport, anyhow::Error> {
+        let _running = self.running.lock().await;
+        self.record(sync_replica(replicas, settings).await)
+    }
+
+    /// Synchronizes the replica, unless a synchronization is running already.
+    /// In this case `None` is returned.
+    pub async fn try_sync(
+        &self,
+        replicas: &ReplicaPool,
+        settings: &SyncSettings,
+    ) -> Option<Result<SyncReport, anyhow::Error>> {
+        let _running = self.running.try_lock().ok()?;
+        Some(self.record(sync_replica(replicas, settings).await))
+    }
+
+    fn record(
+        &self,
+        result: Result<SyncReport, anyhow::Error>,
+    ) -> Result<SyncReport, anyhow::Error> {
+        if let Ok(mut status) = self.status.write() {
+            let now = Utc::now();
+            status.last_attempt = Some(now);
+            match &result {
+                Ok(report) => {
+                    status.last_success = Some(now);
+                    status.last_report = Some(*report);
+                    status.last_error = None;
+                    status.failures = 0;
+                }
+                Err(e) => {
+                    status.last_error = Some(e.to_string());
+                    status.failures = status.failures.saturating_add(1);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Delay until the next background synchronization.
+/// Every failure doubles the interval, up to an hour
+/// or the interval itself if that is longer.
+pub fn sync_delay(interval: Duration, failures: u32) -> Duration {
+    let backoff = interval.saturating_mul(2_u32.saturating_pow(failures.min(16)));
+    backoff.min(MAX_SYNC_BACKOFF.max(interval))
+}
+
+/// Starts the background task synchronizing the replica every `TWK_SYNC` seconds.
+/// Nothing is started if no interval or no sync server is configured.
+pub fn spawn_sync_scheduler(app_state: &AppState) -> Option<JoinHandle<()>> {
+    let interval = u64::try_from(app_state.sync_interval)
+        .ok()
+        .filter(|p| *p > 0)
+        .map(Duration::from_secs)?;
+    let Some(settings) = app_state.app_config.sync.clone() else {
+        info!("TWK_SYNC is set, but no sync server is configured");
+        return None;
+    };
+    let replicas = app_state.replica.clone();
+    let sync_state = app_state.sync_state.clone();
+    info!(
+        "Synchronizing the replica every {} seconds",
+        interval.as_secs()
+    );
+    Some(tokio::spawn(async move {
+        let mut delay = interval;
+        loop {
+            tokio::time::sleep(delay).await;
+            match sync_state.try_sync(&replicas, &settings).await {
+                None => debug!("Synchronization is running already, skipping this cycle"),
+                Some(Ok(_)) => delay = interval,
+                Some(Err(e)) => {
+                    let failures = sync_state.status().failures;
+                    delay = sync_delay(interval, failures);
+                    error!(
+                        "Background synchronization failed {} times, retrying in {} seconds: {:?}",
+                        failures,
+                        delay.as_secs(),
+                        e
+                    );
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,181 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use taskchampion::{Operations, Status, Uuid};
+
+use super::{SyncReport, SyncState, SyncStatus, sync_delay, sync_replica};
+use crate::backend::replica::ReplicaPool;
+use crate::backend::task::get_replica;
+use crate::core::config::{LocalSyncServer, RemoteSyncServer, SyncSettings};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_local_server() -> anyhow::Result<()> {
+    let server_dir = tempfile::tempdir()?;
+    let replica_a = tempfile::tempdir()?;
+    let replica_b = tempfile::tempdir()?;
+    let settings = SyncSettings {
+        local: Some(LocalSyncServer {
+            server_dir: server_dir.path().to_path_buf(),
+        }),
+        ..SyncSettings::default()
+    };
+
+    let uuid = Uuid::new_v4();
+    let mut replica = get_replica(replica_a.path()).await?;
+    let mut ops = Operations::new();
+    let mut task = replica.create_task(uuid, &mut ops).await?;
+    task.set_description("Synchronized task".into(), &mut ops)?;
+    task.set_status(Status::Pending, &mut ops)?;
+    replica.commit_operations(ops).await?;
+
+    let report = sync_replica(&ReplicaPool::new(replica_a.path().into()), &settings).await?;
+    assert_eq!(
+        report,
+        SyncReport {
+            pulled: 0,
+            pushed: 1
+        }
+    );
+
+    let report = sync_replica(&ReplicaPool::new(replica_b.path().into()), &settings).await?;
+    assert_eq!(
+        report,
+        SyncReport {
+            pulled: 1,
+            pushed: 0
+        }
+    );
+    let mut replica = get_replica(replica_b.path()).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_description(), "Synchronized task");
+
+    let report = sync_replica(&ReplicaPool::new(replica_a.path().into()), &settings).await?;
+    assert_eq!(report, SyncReport::default());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_server_timeout() -> anyhow::Result<()> {
+    // accepts connections, but never answers.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let mut connections = vec![];
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    let replica = tempfile::tempdir()?;
+    let replicas = ReplicaPool::new(replica.path().into());
+    let settings = SyncSettings {
+        server: Some(RemoteSyncServer {
+            url,
+            client_id: Uuid::new_v4().to_string(),
+        }),
+        encryption_secret: Some("shared secret".into()),
+        request_timeout: Some(1),
+        ..SyncSettings::default()
+    };
+    let started = std::time::Instant::now();
+    let result = sync_replica(&replicas, &settings).await;
+    let error = format!("{:#}", result.unwrap_err());
+    assert!(error.contains("No answer from the sync server within 1s"));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    // the replica is free for changes again.
+    drop(replicas.write().await?);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_misconfigured() {
+    let replica = tempfile::tempdir().unwrap();
+    let settings = SyncSettings {
+        server: Some(RemoteSyncServer {
+            url: "https://sync.example.com".into(),
+            client_id: Uuid::new_v4().to_string(),
+        }),
+        ..SyncSettings::default()
+    };
+    let result = sync_replica(&ReplicaPool::new(replica.path().into()), &settings).await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("encryption secret")
+    );
+    assert!(
+        sync_replica(
+            &ReplicaPool::new(replica.path().into()),
+            &SyncSettings::default()
+        )
+        .await
+        .is_err()
+    );
+}
+
+#[test]
+fn test_sync_delay() {
+    let interval = std::time::Duration::from_secs(60);
+    assert_eq!(sync_delay(interval, 0), interval);
+    assert_eq!(sync_delay(interval, 1).as_secs(), 120);
+    assert_eq!(sync_delay(interval, 3).as_secs(), 480);
+    assert_eq!(sync_delay(interval, 10).as_secs(), 3600);
+    assert_eq!(sync_delay(interval, u32::MAX).as_secs(), 3600);
+    let interval = std::time::Duration::from_secs(7200);
+    assert_eq!(sync_delay(interval, 5), interval);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_state() -> anyhow::Result<()> {
+    let server_dir = tempfile::tempdir()?;
+    let replica = tempfile::tempdir()?;
+    let sync_state = SyncState::default();
+    assert_eq!(sync_state.status(), SyncStatus::default());
+
+    let result = sync_state
+        .sync(
+            &ReplicaPool::new(replica.path().into()),
+            &SyncSettings::default(),
+        )
+        .await;
+    assert!(result.is_err());
+    let status = sync_state.status();
+    assert_eq!(status.failures, 1);
+    assert!(status.last_error.is_some());
+    assert!(status.last_attempt.is_some());
+    assert!(status.last_success.is_none());
+
+    let settings = SyncSettings {
+        local: Some(LocalSyncServer {
+            server_dir: server_dir.path().to_path_buf(),
+        }),
+        ..SyncSettings::default()
+    };
+    let running = sync_state.running.clone();
+    let guard = running.lock().await;
+    assert!(sync_state.is_running());
+    assert!(
+        sync_state
+            .try_sync(&ReplicaPool::new(replica.path().into()), &settings)
+            .await
+            .is_none()
+    );
+    drop(guard);
+
+    let report = sync_state
+        .try_sync(&ReplicaPool::new(replica.path().into()), &settings)
+        .await;
+    assert_eq!(report.unwrap()?, SyncReport::default());
+    let status = sync_state.status();
+    assert_eq!(status.failures, 0);
+    assert!(status.last_error.is_none());
+    assert_eq!(status.last_report, Some(SyncReport::default()));
+    Ok(())
+}
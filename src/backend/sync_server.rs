@@ -0,0 +1,265 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Storage of the embedded taskchampion sync server.
+//!
+//! Every client id gets its own directory below the storage root:
+//! - `client.json` holds the latest version and the snapshot version,
+//! - `versions/<parent version id>` holds the child version of the parent,
+//! - `ids/<version id>` holds the sequence number of the version,
+//! - `snapshot` holds the latest snapshot.
+//!
+//! The payloads are encrypted by the clients and stored as given.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use taskchampion::Uuid;
+use taskchampion::server::{NIL_VERSION_ID, SnapshotUrgency, VersionId};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ClientState {
+    latest_version_id: VersionId,
+    latest_seq: u64,
+    snapshot_version_id: Option<VersionId>,
+    snapshot_seq: u64,
+}
+
+/// A version as stored on the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredVersion {
+    pub version_id: VersionId,
+    pub parent_version_id: VersionId,
+    pub history_segment: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddVersionOutcome {
+    Added {
+        version_id: VersionId,
+        snapshot_urgency: SnapshotUrgency,
+    },
+    /// The client has to rebase its changes on the given version.
+    ExpectedParentVersion(VersionId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildVersionOutcome {
+    Found(StoredVersion),
+    /// The client is up to date.
+    UpToDate,
+    /// The parent version is unknown, the client has to start from the snapshot.
+    Gone,
+}
+
+/// Version storage of the embedded sync server, shared by all requests.
+#[derive(Clone)]
+pub struct SyncServerStorage {
+    root: PathBuf,
+    snapshot_versions: u64,
+    lock: Arc<Mutex<()>>,
+}
+
+impl SyncServerStorage {
+    /// Creates the storage below `root`, snapshots are requested
+    /// once `snapshot_versions` versions were added since the last one.
+    pub fn new(root: PathBuf, snapshot_versions: u64) -> Self {
+        Self {
+            root,
+            snapshot_versions: snapshot_versions.max(1),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn client_dir(&self, client_id: Uuid) -> PathBuf {
+        self.root.join(client_id.to_string())
+    }
+
+    async fn read_client(&self, client_id: Uuid) -> Result<Option<ClientState>, anyhow::Error> {
+        match fs::read(self.client_dir(client_id).join("client.json")).await {
+            Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write_client(
+        &self,
+        client_id: Uuid,
+        state: &ClientState,
+    ) -> Result<(), anyhow::Error> {
+        write_atomic(
+            &self.client_dir(client_id).join("client.json"),
+            &serde_json::to_vec(state)?,
+        )
+        .await
+    }
+
+    fn snapshot_urgency(&self, state: &ClientState) -> SnapshotUrgency {
+        let versions_since_snapshot = state.latest_seq - state.snapshot_seq;
+        if versions_since_snapshot >= self.snapshot_versions * 2 {
+            SnapshotUrgency::High
+        } else if versions_since_snapshot >= self.snapshot_versions {
+            SnapshotUrgency::Low
+        } else {
+            SnapshotUrgency::None
+        }
+    }
+
+    /// Adds a new version on top of the latest version of the client.
+    /// Unknown clients are created with their first version.
+    pub async fn add_version(
+        &self,
+        client_id: Uuid,
+        parent_version_id: VersionId,
+        history_segment: &[u8],
+    ) -> Result<AddVersionOutcome, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let mut state = self.read_client(client_id).await?.unwrap_or_default();
+        if state.latest_version_id != NIL_VERSION_ID && state.latest_version_id != parent_version_id
+        {
+            return Ok(AddVersionOutcome::ExpectedParentVersion(
+                state.latest_version_id,
+            ));
+        }
+
+        let client_dir = self.client_dir(client_id);
+        fs::create_dir_all(client_dir.join("versions")).await?;
+        fs::create_dir_all(client_dir.join("ids")).await?;
+        let version_id = Uuid::new_v4();
+        let mut content = format!("{version_id}\n").into_bytes();
+        content.extend_from_slice(history_segment);
+        write_atomic(
+            &client_dir
+                .join("versions")
+                .join(parent_version_id.to_string()),
+            &content,
+        )
+        .await?;
+        state.latest_seq += 1;
+        write_atomic(
+            &client_dir.join("ids").join(version_id.to_string()),
+            state.latest_seq.to_string().as_bytes(),
+        )
+        .await?;
+        state.latest_version_id = version_id;
+        self.write_client(client_id, &state).await?;
+
+        Ok(AddVersionOutcome::Added {
+            version_id,
+            snapshot_urgency: self.snapshot_urgency(&state),
+        })
+    }
+
+    /// Looks up the version following the given parent version.
+    pub async fn get_child_version(
+        &self,
+        client_id: Uuid,
+        parent_version_id: VersionId,
+    ) -> Result<ChildVersionOutcome, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let Some(state) = self.read_client(client_id).await? else {
+            return Ok(ChildVersionOutcome::UpToDate);
+        };
+        let path = self
+            .client_dir(client_id)
+            .join("versions")
+            .join(parent_version_id.to_string());
+        match fs::read(path).await {
+            Ok(content) => {
+                let Some(split) = content.iter().position(|p| *p == b'\n') else {
+                    anyhow::bail!("Stored version of client {client_id} is corrupted");
+                };
+                let version_id = Uuid::parse_str(std::str::from_utf8(&content[..split])?)?;
+                Ok(ChildVersionOutcome::Found(StoredVersion {
+                    version_id,
+                    parent_version_id,
+                    history_segment: content[split + 1..].to_vec(),
+                }))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if parent_version_id == state.latest_version_id {
+                    Ok(ChildVersionOutcome::UpToDate)
+                } else {
+                    Ok(ChildVersionOutcome::Gone)
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores the snapshot of the given version.
+    /// Snapshots of unknown versions or older than the stored one are ignored.
+    /// Returns whether the snapshot was stored.
+    pub async fn add_snapshot(
+        &self,
+        client_id: Uuid,
+        version_id: VersionId,
+        snapshot: &[u8],
+    ) -> Result<bool, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let Some(mut state) = self.read_client(client_id).await? else {
+            return Ok(false);
+        };
+        let client_dir = self.client_dir(client_id);
+        let seq =
+            match fs::read_to_string(client_dir.join("ids").join(version_id.to_string())).await {
+                Ok(seq) => seq.trim().parse::<u64>()?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                Err(e) => return Err(e.into()),
+            };
+        if state.snapshot_version_id.is_some() && seq <= state.snapshot_seq {
+            return Ok(false);
+        }
+        write_atomic(&client_dir.join("snapshot"), snapshot).await?;
+        state.snapshot_version_id = Some(version_id);
+        state.snapshot_seq = seq;
+        self.write_client(client_id, &state).await?;
+        Ok(true)
+    }
+
+    /// Returns the latest snapshot of the client, if there is one.
+    pub async fn get_snapshot(
+        &self,
+        client_id: Uuid,
+    ) -> Result<Option<(VersionId, Vec<u8>)>, anyhow::Error> {
+        let _guard = self.lock.lock().await;
+        let Some(version_id) = self
+            .read_client(client_id)
+            .await?
+            .and_then(|p| p.snapshot_version_id)
+        else {
+            return Ok(None);
+        };
+        let snapshot = fs::read(self.client_dir(client_id).join("snapshot")).await?;
+        Ok(Some((version_id, snapshot)))
+    }
+}
+
+/// Writes the file through a temporary file, so readers never see partial content.
+pub(crate) async fn write_atomic(path: &Path, content: &[u8]) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
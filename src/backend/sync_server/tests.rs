@@ -0,0 +1,129 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use taskchampion::Uuid;
+use taskchampion::server::{NIL_VERSION_ID, SnapshotUrgency};
+
+use super::{AddVersionOutcome, ChildVersionOutcome, SyncServerStorage};
+
+fn added_version(outcome: AddVersionOutcome) -> Uuid {
+    match outcome {
+        AddVersionOutcome::Added { version_id, .. } => version_id,
+        AddVersionOutcome::ExpectedParentVersion(_) => panic!("Version was not added"),
+    }
+}
+
+#[tokio::test]
+async fn test_sync_server_versions() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let storage = SyncServerStorage::new(tmp_dir.path().to_path_buf(), 100);
+    let client_id = Uuid::new_v4();
+
+    assert_eq!(
+        storage.get_child_version(client_id, NIL_VERSION_ID).await?,
+        ChildVersionOutcome::UpToDate
+    );
+    let first = added_version(
+        storage
+            .add_version(client_id, NIL_VERSION_ID, b"first")
+            .await?,
+    );
+    let second = added_version(storage.add_version(client_id, first, b"second").await?);
+    assert_eq!(
+        storage.add_version(client_id, first, b"stale").await?,
+        AddVersionOutcome::ExpectedParentVersion(second)
+    );
+
+    match storage.get_child_version(client_id, first).await? {
+        ChildVersionOutcome::Found(version) => {
+            assert_eq!(version.version_id, second);
+            assert_eq!(version.parent_version_id, first);
+            assert_eq!(version.history_segment, b"second");
+        }
+        outcome => panic!("Unexpected outcome {outcome:?}"),
+    }
+    assert_eq!(
+        storage.get_child_version(client_id, second).await?,
+        ChildVersionOutcome::UpToDate
+    );
+    assert_eq!(
+        storage.get_child_version(client_id, Uuid::new_v4()).await?,
+        ChildVersionOutcome::Gone
+    );
+
+    // other clients do not see the versions
+    let other_client = Uuid::new_v4();
+    assert_eq!(
+        storage
+            .get_child_version(other_client, NIL_VERSION_ID)
+            .await?,
+        ChildVersionOutcome::UpToDate
+    );
+    assert!(tmp_dir.path().join(client_id.to_string()).is_dir());
+    assert!(!tmp_dir.path().join(other_client.to_string()).exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_server_snapshots() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let storage = SyncServerStorage::new(tmp_dir.path().to_path_buf(), 2);
+    let client_id = Uuid::new_v4();
+
+    assert_eq!(storage.get_snapshot(client_id).await?, None);
+    let first = storage.add_version(client_id, NIL_VERSION_ID, b"1").await?;
+    assert!(matches!(
+        first,
+        AddVersionOutcome::Added {
+            snapshot_urgency: SnapshotUrgency::None,
+            ..
+        }
+    ));
+    let first = added_version(first);
+    let second = storage.add_version(client_id, first, b"2").await?;
+    assert!(matches!(
+        second,
+        AddVersionOutcome::Added {
+            snapshot_urgency: SnapshotUrgency::Low,
+            ..
+        }
+    ));
+    let second = added_version(second);
+    let third = added_version(storage.add_version(client_id, second, b"3").await?);
+    let fourth = storage.add_version(client_id, third, b"4").await?;
+    assert!(matches!(
+        fourth,
+        AddVersionOutcome::Added {
+            snapshot_urgency: SnapshotUrgency::High,
+            ..
+        }
+    ));
+
+    assert!(
+        !storage
+            .add_snapshot(client_id, Uuid::new_v4(), b"unknown")
+            .await?
+    );
+    assert!(
+        storage
+            .add_snapshot(client_id, third, b"snapshot 3")
+            .await?
+    );
+    assert!(
+        !storage
+            .add_snapshot(client_id, second, b"snapshot 2")
+            .await?
+    );
+    assert_eq!(
+        storage.get_snapshot(client_id).await?,
+        Some((third, b"snapshot 3".to_vec()))
+    );
+    Ok(())
+}
@@ -8,28 +8,19 @@
  * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+use crate::backend::hooks::{TaskEvent, run_exit_hooks, run_hooks_on_task};
+use crate::backend::replica::ReplicaPool;
 use crate::backend::serde::{task_date_format, task_date_format_mandatory, task_status_serde};
+use crate::backend::webhooks::queue_webhooks;
 use crate::core::app::AppState;
 use crate::core::errors::AppError;
-use anyhow::{Error, bail};
-use chrono::{DateTime, TimeZone, Utc, offset::LocalResult};
+use anyhow::{Error, anyhow, bail};
+use chrono::{DateTime, TimeZone, Timelike, Utc, offset::LocalResult};
 use serde::{Deserialize, Serialize};
-use std::fs::DirEntry;
-use std::{
-    collections::HashMap,
-    fmt::Display,
-    io::Write,
-    os::unix::fs::PermissionsExt,
-    path::{Path, PathBuf},
-    process::{Command, Stdio},
-};
+use std::{collections::HashMap, fmt::Display, path::Path};
 use taskchampion::{Operation, Operations, Replica, SqliteStorage, Uuid};
-use tracing::{debug, error, info};
-
-#[cfg(windows)]
-const LINE_ENDING: &str = "\r\n";
-#[cfg(not(windows))]
-const LINE_ENDING: &str = "\n";
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
 
 #[derive(Clone, Debug)]
 pub enum TaskProperties {
@@ -84,7 +75,7 @@ impl TryFrom<&str> for TaskProperties {
             || {
                 Err(Error::msg(format!(
                     "Property {} is not a reserved property.",
-                    &value
+                    value
                 )))
             },
             |x| Ok(x.to_owned()),
@@ -103,28 +94,6 @@ pub fn convert_task_status(task_status: &str) -> taskchampion::Status {
     }
 }
 
-/// Supported hook events based on taskwarrior definitions.
-///
-/// `OnAdd` requires executable script named with starting `on-add` and is executed
-/// on new definition of a task.
-/// `OnModify` requires executable script named with starting `on-modify` and is executed
-/// whenever a task is changed.
-#[derive(Clone, Debug)]
-pub enum TaskEvent {
-    OnAdd,
-    OnModify,
-}
-
-impl Display for TaskEvent {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Self::OnAdd => "on-add".to_string(),
-            Self::OnModify => "on-modify".to_string(),
-        };
-        write!(f, "{s}")
-    }
-}
-
 #[derive(Debug, Clone)]
 struct TcDateConverter(String);
 
@@ -151,13 +120,31 @@ impl TcDateConverter {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Eq, Ord, ToSchema)]
 pub struct Annotation {
     #[serde(with = "task_date_format_mandatory")]
+    #[schema(value_type = String, example = "20250501T120000Z")]
     entry: DateTime<Utc>,
     description: String,
 }
 
+impl Annotation {
+    pub fn new(entry: DateTime<Utc>, description: &str) -> Self {
+        Self {
+            entry,
+            description: description.to_string(),
+        }
+    }
+
+    pub const fn entry(&self) -> &DateTime<Utc> {
+        &self.entry
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
 impl From<taskchampion::Annotation> for Annotation {
     fn from(value: taskchampion::Annotation) -> Self {
         Self {
@@ -167,7 +154,8 @@ impl From<taskchampion::Annotation> for Annotation {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// Task in the format of `task export`, dates are given like `20250501T120000Z`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, ToSchema)]
 pub struct Task {
     // id is the relative number within the working set!
     // to be retrieved with replica.working_set
@@ -175,33 +163,43 @@ pub struct Task {
     #[serde(skip_serializing_if = "String::is_empty")]
     pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end: Option<String>,
+    #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
+    pub end: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project: Option<String>,
     pub uuid: Uuid,
     pub urgency: Option<f64>,
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub entry: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub start: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub until: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub scheduled: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub annotations: Option<Vec<Annotation>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub due: Option<DateTime<Utc>>,
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub modified: Option<DateTime<Utc>>,
     #[serde(default, with = "task_status_serde")]
+    #[schema(value_type = Option<String>, example = "pending")]
     pub status: Option<taskchampion::Status>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "task_date_format")]
+    #[schema(value_type = Option<String>)]
     pub wait: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
@@ -219,6 +217,10 @@ pub struct Task {
     pub parent: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "UDA")]
     pub uda: Option<HashMap<String, String>>,
+    // synthetic tags like `ACTIVE` or `BLOCKED` as computed by taskchampion,
+    // only used for filtering and never part of the exported json.
+    #[serde(skip)]
+    pub synthetic_tags: Vec<String>,
 }
 
 impl From<taskchampion::Task> for Task {
@@ -228,6 +230,11 @@ impl From<taskchampion::Task> for Task {
             .filter(taskchampion::Tag::is_user)
             .map(|p| p.to_string())
             .collect();
+        let synthetic_tags: Vec<String> = value
+            .get_tags()
+            .filter(taskchampion::Tag::is_synthetic)
+            .map(|p| p.to_string())
+            .collect();
         let deps: Vec<Uuid> = value.get_dependencies().collect();
         let mut annotations: Vec<Annotation> =
             value.get_annotations().map(Annotation::from).collect();
@@ -244,13 +251,13 @@ impl From<taskchampion::Task> for Task {
             description: value.get_description().to_string(),
             tags: Some(tags),
             depends: Some(deps),
-            end: value.get_value("start").map(ToString::to_string),
+            // timestamp
+            end: TcDateConverter::convert_to_datetime(value.get_value("end").map(Into::into)),
             project: value
                 .get_value(TaskProperties::Project.to_string())
                 .map(ToString::to_string),
-            urgency: value
-                .get_value("urgency")
-                .map(|p| p.parse::<f64>().unwrap_or_default()),
+            // urgency is not stored, it is computed with `UrgencyCoefficients`
+            urgency: None,
             // timestamp
             entry: TcDateConverter::convert_to_datetime(value.get_value("entry").map(Into::into)),
             // timestamp
@@ -273,6 +280,7 @@ impl From<taskchampion::Task> for Task {
                 .map(|p| p.parse::<f64>().unwrap_or_default()),
             parent: value.get_value("parent").map(ToString::to_string),
             uda: Some(uda),
+            synthetic_tags,
         }
     }
 }
@@ -283,7 +291,7 @@ impl Task {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaskOperation {
     pub operation: String,
     pub uuid: Uuid,
@@ -336,145 +344,27 @@ pub async fn get_replica(taskdb: &Path) -> Result<Replica<SqliteStorage>, anyhow
     Ok(Replica::new(storage))
 }
 
-/// Executes hook scripts based on the type of task events.
-///
-/// # Behavior
-///
-/// - If `hooks_dir` is `None`, or if the directory does not exist, the function
-///   logs a warning and exits early.
-/// - Hook scripts are identified as executable files with names that start with
-///   the string representation of the `event_type`.
-///
-/// # Notes
-///
-/// - This function uses Unix-style file permission checks to determine
-///   whether a file is executable.
-/// - Hook scripts must conform to the naming conventions (beginning with the
-///   task event type) to be picked up for execution.
+/// Get read-only replica access to the taskchampion database stored in `taskdb`.
+/// Reading does not wait for the write lock, which the Taskwarrior CLI may hold.
+/// # Errors
 ///
-pub fn execute_hooks(
-    hooks_dir: &Option<PathBuf>,
-    event_type: &TaskEvent,
-    old: &Option<Task>,
-    new: &Option<Task>,
-) {
-    let old_task = old
-        .as_ref()
-        .map(|f| serde_json::to_string(&f).unwrap_or_default())
-        .unwrap_or_default();
-
-    let new_task = new
-        .as_ref()
-        .map(|f| serde_json::to_string(&f).unwrap_or_default())
-        .unwrap_or_default();
-
-    let mut args = String::new();
-    if matches!(event_type, TaskEvent::OnModify) {
-        args.push_str(&old_task);
-        args.push_str(LINE_ENDING);
-    }
-    args.push_str(&new_task);
-    args.push_str(LINE_ENDING);
-
-    // find scripts and execute.
-    let Some(hooks_dir) = hooks_dir.as_ref() else {
-        tracing::warn!("Hooks directory is not set, skipping hook execution.");
-        return;
-    };
-
-    let Ok(paths) = std::fs::read_dir(hooks_dir) else {
-        tracing::warn!(
-            "Failed to read Hooks directory {:?}, skipping hook execution.",
-            hooks_dir
-        );
-        return;
-    };
-    paths
-        .filter_map(|dir_entry| {
-            let Ok(entry) = dir_entry else {
-                return None;
-            };
-
-            let Ok(meta) = entry.metadata() else {
-                return None;
-            };
-
-            let permissions = meta.permissions();
-            let is_executable = permissions.mode() & 0o111 != 0;
-
-            if !meta.is_file() || !is_executable {
-                return None;
-            }
-
-            if let Some(name) = entry.file_name().to_str()
-                && name.starts_with(&event_type.to_string())
-            {
-                return Some(entry);
-            }
-            None
-        })
-        .for_each(|entry| {
-            execute_hook_file(&args, &entry);
-        });
-}
-
-fn execute_hook_file(args: &str, entry: &DirEntry) {
-    debug!(
-        "Hook {:?} will be executed with stdin: {}",
-        &entry.file_name().to_str(),
-        &args
-    );
-    Command::new(entry.path())
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .map_or_else(
-            |e| {
-                error!(
-                    "Hook {:?} failed to spawn with error {}",
-                    &entry.file_name().to_str(),
-                    e.to_string()
-                );
-            },
-            |mut child| {
-                let child_stdin = child.stdin.as_mut().unwrap();
-                let _ = child_stdin.write_all(args.as_bytes());
-
-                let cmd = child.wait_with_output();
-                match cmd {
-                    Ok(o) => {
-                        let output = [o.stdout.as_slice()].concat();
-                        let output = String::from_utf8(output)
-                            .unwrap_or_else(|_| "Output no valid UTF-8".to_string());
-                        info!(
-                            "Hook {:?} called, exit status: {}",
-                            &entry.file_name().to_str(),
-                            o.status
-                        );
-                        debug!(
-                            "Hook {:?} output was {:?}",
-                            &entry.file_name().to_str(),
-                            output
-                        );
-                    }
-                    Err(e) => {
-                        error!(
-                            "Hook {:?} failed with error {}",
-                            &entry.file_name().to_str(),
-                            e.to_string()
-                        );
-                    }
-                }
-            },
-        );
+/// Will return error if unable to access replica
+pub async fn get_read_only_replica(taskdb: &Path) -> Result<Replica<SqliteStorage>, anyhow::Error> {
+    let storage = SqliteStorage::new(
+        taskdb.to_path_buf(),
+        taskchampion::storage::AccessMode::ReadOnly,
+        true,
+    )
+    .await?;
+    Ok(Replica::new(storage))
 }
 
 /// Gives a dedup list of projects in the given taskchampion data source.
 /// # Errors
 ///
 /// Will return error if unable to access replica
-pub async fn get_project_list(taskdb: &Path) -> Result<Vec<String>, AppError> {
-    let mut replica = get_replica(taskdb).await?;
+pub async fn get_project_list(replicas: &ReplicaPool) -> Result<Vec<String>, AppError> {
+    let mut replica = replicas.read().await?;
     let mut x = replica
         .all_task_data()
         .await?
@@ -498,8 +388,8 @@ pub async fn get_project_list(taskdb: &Path) -> Result<Vec<String>, AppError> {
 
 /// Get a list of tags used in any of current worksets replica content.
 #[allow(dead_code)]
-async fn get_tag_list(taskdb: &Path) -> Result<Vec<String>, AppError> {
-    let mut replica = get_replica(taskdb).await?;
+async fn get_tag_list(replicas: &ReplicaPool) -> Result<Vec<String>, AppError> {
+    let mut replica = replicas.read().await?;
     let mut tags: Vec<String> = vec![];
 
     for task in replica.all_task_data().await? {
@@ -514,65 +404,151 @@ async fn get_tag_list(taskdb: &Path) -> Result<Vec<String>, AppError> {
     Ok(tags)
 }
 
-pub async fn get_undo_operations(
-    taskdb: &Path,
-) -> Result<HashMap<Uuid, Vec<TaskOperation>>, AppError> {
-    let mut replica = get_replica(taskdb).await?;
-    let ops = replica.get_undo_operations().await?;
-    let mut converted_ops: HashMap<Uuid, Vec<TaskOperation>> = HashMap::new();
-    for op in ops {
-        {
-            let converted_entry = match op {
-                Operation::Create { uuid } => {
-                    Some((uuid, TaskOperation::new("Create", uuid, false)))
-                }
-                Operation::Delete { uuid, old_task } => Some((
-                    uuid,
-                    TaskOperation::new_with_old_task("Delete", uuid, false, old_task),
-                )),
-                Operation::Update {
-                    uuid,
-                    property,
-                    old_value,
-                    value,
-                    timestamp,
-                } => {
-                    let is_tag_change = property.starts_with("tag_");
-                    let property = property
-                        .strip_prefix("tag_")
-                        .unwrap_or(&property)
-                        .to_string();
-                    Some((
-                        uuid,
-                        TaskOperation {
-                            operation: "Modified".to_string(),
-                            uuid,
-                            property: Some(property),
-                            old_value,
-                            value,
-                            timestamp: Some(timestamp),
-                            old_task: None,
-                            is_tag_change,
-                        },
-                    ))
-                }
-                Operation::UndoPoint => None,
-            };
-            if let Some(top) = converted_entry {
-                if let Some(op_list) = converted_ops.get_mut(&top.0) {
-                    op_list.push(top.1);
-                } else {
-                    converted_ops.insert(top.0, vec![top.1]);
-                }
+/// Local changes between two undo points, as shown in the undo history.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UndoGroup {
+    /// Operations in the order they were applied.
+    pub operations: Vec<TaskOperation>,
+    /// Time of the most recent modification within the group, if known.
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl UndoGroup {
+    fn new(operations: Vec<TaskOperation>) -> Self {
+        let timestamp = operations.iter().filter_map(|p| p.timestamp).max();
+        Self {
+            operations,
+            timestamp,
+        }
+    }
+}
+
+fn convert_operation(op: Operation) -> Option<TaskOperation> {
+    match op {
+        Operation::Create { uuid } => Some(TaskOperation::new("Create", uuid, false)),
+        Operation::Delete { uuid, old_task } => Some(TaskOperation::new_with_old_task(
+            "Delete", uuid, false, old_task,
+        )),
+        Operation::Update {
+            uuid,
+            property,
+            old_value,
+            value,
+            timestamp,
+        } => {
+            let is_tag_change = property.starts_with("tag_");
+            let property = property
+                .strip_prefix("tag_")
+                .unwrap_or(&property)
+                .to_string();
+            Some(TaskOperation {
+                operation: "Modified".to_string(),
+                uuid,
+                property: Some(property),
+                old_value,
+                value,
+                timestamp: Some(timestamp),
+                old_task: None,
+                is_tag_change,
+            })
+        }
+        Operation::UndoPoint => None,
+    }
+}
+
+/// Lists all local changes which were not synchronized yet, grouped by undo point.
+/// The most recent group comes first, it is the one reverted by a single undo.
+pub async fn get_undo_history(replicas: &ReplicaPool) -> Result<Vec<UndoGroup>, anyhow::Error> {
+    let operations = replicas.unsynced_operations().await?;
+    let mut groups = vec![];
+    let mut current: Vec<TaskOperation> = vec![];
+    for op in operations {
+        if op.is_undo_point() {
+            if !current.is_empty() {
+                groups.push(UndoGroup::new(std::mem::take(&mut current)));
             }
+        } else {
+            current.extend(convert_operation(op));
         }
     }
+    if !current.is_empty() {
+        groups.push(UndoGroup::new(current));
+    }
+    groups.reverse();
+    Ok(groups)
+}
 
-    Ok(converted_ops)
+/// Reverts the most recent `steps` undo points through the replica.
+///
+/// Returns how many undo points were reverted, which is less than `steps`
+/// if there are not enough local changes, and the tasks which were changed,
+/// as they were before the undo. Tasks which did not exist yet are `None`.
+///
+/// Each undo point is committed on its own. If one cannot be reverted,
+/// the undo stops there and the undo points reverted so far are returned,
+/// it only fails if not even the first one could be reverted.
+pub async fn undo_operations(
+    replicas: &ReplicaPool,
+    steps: usize,
+) -> Result<(usize, Vec<(Uuid, Option<Task>)>), anyhow::Error> {
+    let mut replica = replicas.write().await?;
+    let working_set = replica.working_set().await?;
+    let mut reverted = 0;
+    let mut changed: Vec<(Uuid, Option<Task>)> = vec![];
+    while reverted < steps {
+        let ops = replica.get_undo_operations().await?;
+        if ops.is_empty() {
+            break;
+        }
+        // undo points without any change are dropped, but do not count as a step.
+        let is_empty_group = ops.iter().all(Operation::is_undo_point);
+        let mut group_changed: Vec<(Uuid, Option<Task>)> = vec![];
+        for uuid in ops.iter().filter_map(Operation::get_uuid) {
+            if changed.iter().chain(&group_changed).any(|p| p.0 == uuid) {
+                continue;
+            }
+            let old = replica.get_task(uuid).await?.map(|task_found| {
+                let mut task = Task::from(task_found);
+                task.set_id(
+                    working_set
+                        .by_uuid(uuid)
+                        .and_then(|p| i64::try_from(p).ok()),
+                );
+                task
+            });
+            group_changed.push((uuid, old));
+        }
+        let error = match replica.commit_reversed_operations(ops).await {
+            Ok(true) => None,
+            Ok(false) if is_empty_group => None,
+            Ok(false) => Some(anyhow!(
+                "Undo failed, the task database was changed concurrently"
+            )),
+            Err(e) => Some(e.into()),
+        };
+        if let Some(e) = error {
+            if reverted == 0 {
+                return Err(e);
+            }
+            warn!(
+                "Undo stopped after {} of {} undo points: {:?}",
+                reverted, steps, e
+            );
+            break;
+        }
+        changed.extend(group_changed);
+        if !is_empty_group {
+            reverted += 1;
+        }
+    }
+    Ok((reverted, changed))
 }
 
-pub async fn get_task(taskdb: &Path, task_id: Uuid) -> Result<Option<Task>, anyhow::Error> {
-    let mut replica = get_replica(taskdb).await?;
+pub async fn get_task(
+    replicas: &ReplicaPool,
+    task_id: Uuid,
+) -> Result<Option<Task>, anyhow::Error> {
+    let mut replica = replicas.read().await?;
     let idx: Option<i64> = replica
         .working_set()
         .await?
@@ -591,37 +567,182 @@ pub async fn get_task(taskdb: &Path, task_id: Uuid) -> Result<Option<Task>, anyh
     Ok(maybe_task)
 }
 
+/// Reads all tasks stored in the replica.
+/// Tasks within the working set get their relative id assigned.
+pub async fn get_tasks(replicas: &ReplicaPool) -> Result<Vec<Task>, anyhow::Error> {
+    let mut replica = replicas.read().await?;
+    let working_set = replica.working_set().await?;
+    let tasks = replica
+        .all_tasks()
+        .await?
+        .into_values()
+        .map(|task_found| {
+            let idx = working_set
+                .by_uuid(task_found.get_uuid())
+                .and_then(|p| i64::try_from(p).ok());
+            let mut task = Task::from(task_found);
+            task.set_id(idx);
+            task
+        })
+        .collect();
+    Ok(tasks)
+}
+
+/// Adds an annotation with the given description to the task.
+/// Annotations are keyed by their entry time, so the entry is moved
+/// forward if another annotation was added within the same second.
+pub async fn annotate_task(
+    task_id: Uuid,
+    description: &str,
+    app_state: &AppState,
+) -> Result<Task, anyhow::Error> {
+    let mut replica = app_state.replica.write().await?;
+    let mut ops = Operations::new();
+    let maybe_task = replica.get_task(task_id).await?;
+    let Some(mut task) = maybe_task else {
+        bail!("Failed to get task");
+    };
+    let old_task = task.clone();
+    let mut entry = Utc::now().with_nanosecond(0).unwrap_or_else(Utc::now);
+    while task.get_annotations().any(|p| p.entry == entry) {
+        entry += chrono::Duration::seconds(1);
+    }
+    ops.push(taskchampion::Operation::UndoPoint);
+    task.add_annotation(
+        taskchampion::Annotation {
+            entry,
+            description: description.to_string(),
+        },
+        &mut ops,
+    )?;
+
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut task,
+        &mut ops,
+    )
+    .await?;
+
+    match replica.commit_operations(ops).await {
+        Ok(()) => {
+            drop(replica);
+            info!("Added task {} annotation {}", task_id.to_string(), entry);
+            let task: Task = task.into();
+            run_exit_hooks(app_state, std::slice::from_ref(&task)).await;
+            queue_webhooks(app_state, Some(&old_task.into()), &task);
+            Ok(task)
+        }
+        Err(e) => {
+            error!(
+                "Could not annotate task {}, error: {}",
+                task_id.to_string(),
+                e.to_string()
+            );
+            Err(e.into())
+        }
+    }
+}
+
+/// Replaces the description of an existing annotation,
+/// the entry of the annotation is kept.
+pub async fn edit_annotation(
+    task_id: Uuid,
+    anno: &Annotation,
+    app_state: &AppState,
+) -> Result<Task, anyhow::Error> {
+    let mut replica = app_state.replica.write().await?;
+    let mut ops = Operations::new();
+    let maybe_task = replica.get_task(task_id).await?;
+    let Some(mut task) = maybe_task else {
+        bail!("Failed to get task");
+    };
+    if !task.get_annotations().any(|p| p.entry == anno.entry) {
+        bail!("The annotation does not exist anymore");
+    }
+    let old_task = task.clone();
+    ops.push(taskchampion::Operation::UndoPoint);
+    task.add_annotation(
+        taskchampion::Annotation {
+            entry: anno.entry,
+            description: anno.description.clone(),
+        },
+        &mut ops,
+    )?;
+
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut task,
+        &mut ops,
+    )
+    .await?;
+
+    match replica.commit_operations(ops).await {
+        Ok(()) => {
+            drop(replica);
+            info!(
+                "Changed task {} annotation {}",
+                task_id.to_string(),
+                anno.entry
+            );
+            let task: Task = task.into();
+            run_exit_hooks(app_state, std::slice::from_ref(&task)).await;
+            queue_webhooks(app_state, Some(&old_task.into()), &task);
+            Ok(task)
+        }
+        Err(e) => {
+            error!(
+                "Could not change annotation of task {}, error: {}",
+                task_id.to_string(),
+                e.to_string()
+            );
+            Err(e.into())
+        }
+    }
+}
+
 pub async fn denotate_task(
     task_id: Uuid,
     anno: &Annotation,
     app_state: &AppState,
 ) -> Result<Task, anyhow::Error> {
-    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let mut replica = app_state.replica.write().await?;
     let mut ops = Operations::new();
     let maybe_task = replica.get_task(task_id).await?;
     let Some(mut task) = maybe_task else {
         bail!("Failed to get task");
     };
+    if !task.get_annotations().any(|p| p.entry == anno.entry) {
+        bail!("The annotation does not exist anymore");
+    }
     let old_task = task.clone();
     ops.push(taskchampion::Operation::UndoPoint);
     task.remove_annotation(anno.entry, &mut ops)?;
 
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut task,
+        &mut ops,
+    )
+    .await?;
+
     match replica.commit_operations(ops).await {
         Ok(()) => {
+            drop(replica);
             info!(
                 "Removed task {} annotation {}",
                 task_id.to_string(),
                 anno.entry
             );
-            // execute hooks.
-            let ct: crate::backend::task::Task = task.into();
-            execute_hooks(
-                &app_state.task_hooks_path,
-                &TaskEvent::OnModify,
-                &Some(old_task.into()),
-                &Some(ct.clone()),
-            );
-            Ok(ct)
+            let task: Task = task.into();
+            run_exit_hooks(app_state, std::slice::from_ref(&task)).await;
+            queue_webhooks(app_state, Some(&old_task.into()), &task);
+            Ok(task)
         }
         Err(e) => {
             error!(
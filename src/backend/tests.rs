@@ -51,6 +51,8 @@ async fn check_hook_file_execution() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     task_add(&task, &app_state).await?;
     assert!(gen_file.exists());
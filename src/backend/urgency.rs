@@ -0,0 +1,291 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::task::Task;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Coefficients used to compute the urgency of a task.
+///
+/// The defaults are the same as taskwarrior's `urgency.*.coefficient` settings.
+/// Coefficients which are not configured keep their default,
+/// this includes the entries of the tag, project and uda coefficients.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct UrgencyCoefficients {
+    pub due: f64,
+    pub blocking: f64,
+    pub scheduled: f64,
+    pub active: f64,
+    pub age: f64,
+    pub annotations: f64,
+    pub tags: f64,
+    pub project: f64,
+    pub waiting: f64,
+    pub blocked: f64,
+    /// Age in days at which the age factor reaches its maximum.
+    pub age_max: f64,
+    /// Coefficients for specific tags, like `urgency.user.tag.next.coefficient`.
+    #[serde(deserialize_with = "with_default_user_tag")]
+    pub user_tag: HashMap<String, f64>,
+    /// Coefficients for specific projects, like `urgency.user.project.Home.coefficient`.
+    pub user_project: HashMap<String, f64>,
+    /// Coefficients for user defined attributes, either by name (`estimate`)
+    /// or by name and value (`priority.H`).
+    #[serde(deserialize_with = "with_default_uda")]
+    pub uda: HashMap<String, f64>,
+}
+
+fn default_user_tag() -> HashMap<String, f64> {
+    HashMap::from([(String::from("next"), 15.0)])
+}
+
+fn default_uda() -> HashMap<String, f64> {
+    HashMap::from([
+        (String::from("priority.H"), 6.0),
+        (String::from("priority.M"), 3.9),
+        (String::from("priority.L"), 1.8),
+    ])
+}
+
+fn with_default_user_tag<'de, D>(deserializer: D) -> Result<HashMap<String, f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut coefficients = default_user_tag();
+    coefficients.extend(HashMap::<String, f64>::deserialize(deserializer)?);
+    Ok(coefficients)
+}
+
+fn with_default_uda<'de, D>(deserializer: D) -> Result<HashMap<String, f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut coefficients = default_uda();
+    coefficients.extend(HashMap::<String, f64>::deserialize(deserializer)?);
+    Ok(coefficients)
+}
+
+impl Default for UrgencyCoefficients {
+    fn default() -> Self {
+        Self {
+            due: 12.0,
+            blocking: 8.0,
+            scheduled: 5.0,
+            active: 4.0,
+            age: 2.0,
+            annotations: 1.0,
+            tags: 1.0,
+            project: 1.0,
+            waiting: -3.0,
+            blocked: -5.0,
+            age_max: 365.0,
+            user_tag: default_user_tag(),
+            user_project: HashMap::new(),
+            uda: default_uda(),
+        }
+    }
+}
+
+/// A single contribution to the urgency of a task.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UrgencyTerm {
+    pub name: String,
+    pub factor: f64,
+    pub coefficient: f64,
+    pub urgency: f64,
+}
+
+impl UrgencyTerm {
+    fn new(name: &str, factor: f64, coefficient: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            factor,
+            coefficient,
+            urgency: factor * coefficient,
+        }
+    }
+}
+
+fn has_synthetic_tag(task: &Task, tag: &str) -> bool {
+    task.synthetic_tags.iter().any(|t| t == tag)
+}
+
+/// Factor for tags and annotations: 0.8 for one, 0.9 for two and 1.0 for more entries.
+fn count_factor(count: usize) -> f64 {
+    match count {
+        0 => 0.0,
+        1 => 0.8,
+        2 => 0.9,
+        _ => 1.0,
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / SECONDS_PER_DAY
+}
+
+/// Due factor ranging from 0.2 (due in more than two weeks)
+/// to 1.0 (overdue by a week or more).
+fn due_factor(due: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let days_overdue = days_between(due, now);
+    if days_overdue >= 7.0 {
+        1.0
+    } else if days_overdue >= -14.0 {
+        ((days_overdue + 14.0) * 0.8 / 21.0) + 0.2
+    } else {
+        0.2
+    }
+}
+
+fn age_factor(entry: DateTime<Utc>, now: DateTime<Utc>, age_max: f64) -> f64 {
+    let age = days_between(entry, now);
+    if age_max == 0.0 || age > age_max {
+        1.0
+    } else {
+        age / age_max
+    }
+}
+
+fn uda_value<'a>(task: &'a Task, name: &str) -> Option<&'a str> {
+    if name == "priority" {
+        return task.priority.as_deref().filter(|p| !p.is_empty());
+    }
+    task.uda
+        .as_ref()
+        .and_then(|p| p.get(name))
+        .map(String::as_str)
+        .filter(|p| !p.is_empty())
+}
+
+impl UrgencyCoefficients {
+    /// Lists all terms contributing to the urgency of the task.
+    /// Terms without any contribution are left out.
+    pub fn terms(&self, task: &Task, now: DateTime<Utc>) -> Vec<UrgencyTerm> {
+        let mut terms = vec![
+            UrgencyTerm::new(
+                "project",
+                if task.project.as_ref().is_some_and(|p| !p.is_empty()) {
+                    1.0
+                } else {
+                    0.0
+                },
+                self.project,
+            ),
+            UrgencyTerm::new(
+                "active",
+                if task.start.is_some() { 1.0 } else { 0.0 },
+                self.active,
+            ),
+            UrgencyTerm::new(
+                "scheduled",
+                if task.scheduled.is_some_and(|p| p < now) {
+                    1.0
+                } else {
+                    0.0
+                },
+                self.scheduled,
+            ),
+            UrgencyTerm::new(
+                "waiting",
+                if has_synthetic_tag(task, "WAITING") {
+                    1.0
+                } else {
+                    0.0
+                },
+                self.waiting,
+            ),
+            UrgencyTerm::new(
+                "blocked",
+                if has_synthetic_tag(task, "BLOCKED") {
+                    1.0
+                } else {
+                    0.0
+                },
+                self.blocked,
+            ),
+            UrgencyTerm::new(
+                "blocking",
+                if has_synthetic_tag(task, "BLOCKING") {
+                    1.0
+                } else {
+                    0.0
+                },
+                self.blocking,
+            ),
+            UrgencyTerm::new(
+                "annotations",
+                count_factor(task.annotations.as_ref().map_or(0, Vec::len)),
+                self.annotations,
+            ),
+            UrgencyTerm::new(
+                "tags",
+                count_factor(task.tags.as_ref().map_or(0, Vec::len)),
+                self.tags,
+            ),
+            UrgencyTerm::new(
+                "due",
+                task.due.map_or(0.0, |p| due_factor(p, now)),
+                self.due,
+            ),
+            UrgencyTerm::new(
+                "age",
+                task.entry.map_or(0.0, |p| age_factor(p, now, self.age_max)),
+                self.age,
+            ),
+        ];
+
+        let tags = task.tags.clone().unwrap_or_default();
+        for (tag, coefficient) in &self.user_tag {
+            if tags.contains(tag) {
+                terms.push(UrgencyTerm::new(&format!("tag {tag}"), 1.0, *coefficient));
+            }
+        }
+
+        let project = task.project.clone().unwrap_or_default();
+        for (project_name, coefficient) in &self.user_project {
+            if !project.is_empty() && project.starts_with(project_name.as_str()) {
+                terms.push(UrgencyTerm::new(
+                    &format!("project {project_name}"),
+                    1.0,
+                    *coefficient,
+                ));
+            }
+        }
+
+        for (uda, coefficient) in &self.uda {
+            let matches = match uda.split_once('.') {
+                Some((name, value)) => uda_value(task, name) == Some(value),
+                None => uda_value(task, uda).is_some(),
+            };
+            if matches {
+                terms.push(UrgencyTerm::new(uda, 1.0, *coefficient));
+            }
+        }
+
+        terms.retain(|p| p.urgency != 0.0);
+        terms.sort_by(|lhs, rhs| rhs.urgency.total_cmp(&lhs.urgency));
+        terms
+    }
+
+    /// Computes the urgency of the task.
+    pub fn urgency(&self, task: &Task, now: DateTime<Utc>) -> f64 {
+        self.terms(task, now).iter().map(|p| p.urgency).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,87 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use super::*;
+use chrono::{Duration, TimeZone};
+use taskchampion::Uuid;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 5, 1, 8, 0, 0).unwrap()
+}
+
+fn plain_task() -> Task {
+    Task {
+        uuid: Uuid::new_v4(),
+        description: "plain task".to_string(),
+        entry: Some(now()),
+        tags: Some(vec![]),
+        annotations: Some(vec![]),
+        ..Task::default()
+    }
+}
+
+#[test]
+fn urgency_of_plain_task_is_zero() {
+    let coefficients = UrgencyCoefficients::default();
+    assert!(coefficients.terms(&plain_task(), now()).is_empty());
+    assert_eq!(coefficients.urgency(&plain_task(), now()), 0.0);
+}
+
+#[test]
+fn urgency_terms_follow_taskwarrior_defaults() {
+    let coefficients = UrgencyCoefficients::default();
+    let task = Task {
+        project: Some("Home".to_string()),
+        priority: Some("H".to_string()),
+        tags: Some(vec!["next".to_string()]),
+        start: Some(now()),
+        entry: Some(now() - Duration::days(73)),
+        due: Some(now() + Duration::days(7)),
+        synthetic_tags: vec!["BLOCKING".to_string()],
+        ..plain_task()
+    };
+    let terms = coefficients.terms(&task, now());
+    let urgency_of = |name: &str| {
+        terms
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.urgency)
+            .unwrap()
+    };
+    assert_eq!(urgency_of("tag next"), 15.0);
+    assert_eq!(urgency_of("blocking"), 8.0);
+    assert_eq!(urgency_of("priority.H"), 6.0);
+    assert_eq!(urgency_of("active"), 4.0);
+    assert_eq!(urgency_of("project"), 1.0);
+    assert!((urgency_of("tags") - 0.8).abs() < 1e-9);
+    assert!((urgency_of("age") - 0.4).abs() < 1e-9);
+    // due in 7 days: ((-7 + 14) * 0.8 / 21) + 0.2
+    assert!((urgency_of("due") - 5.6).abs() < 1e-9);
+    assert_eq!(terms[0].name, "tag next");
+
+    let expected = 15.0 + 8.0 + 6.0 + 4.0 + 1.0 + 0.8 + 0.4 + 5.6;
+    assert!((coefficients.urgency(&task, now()) - expected).abs() < 1e-9);
+}
+
+#[test]
+fn blocked_and_waiting_tasks_are_less_urgent() {
+    let coefficients = UrgencyCoefficients::default();
+    let task = Task {
+        synthetic_tags: vec!["BLOCKED".to_string(), "WAITING".to_string()],
+        due: Some(now() - Duration::days(30)),
+        ..plain_task()
+    };
+    assert!((coefficients.urgency(&task, now()) - (12.0 - 5.0 - 3.0)).abs() < 1e-9);
+
+    let task = Task {
+        due: Some(now() + Duration::days(60)),
+        ..plain_task()
+    };
+    assert!((coefficients.urgency(&task, now()) - 2.4).abs() < 1e-9);
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Watches the task database for changes of any writer,
+//! this application as well as the Taskwarrior CLI or a synchronization.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use super::replica::DATABASE_FILES;
+use crate::core::app::AppState;
+
+/// A single commit touches the database files several times,
+/// the changes within this delay are reported once.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(250);
+
+/// Broadcasts the time of every change of the task database to the subscribers.
+#[derive(Clone)]
+pub struct TaskChanges {
+    sender: broadcast::Sender<DateTime<Utc>>,
+}
+
+impl Default for TaskChanges {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(16).0,
+        }
+    }
+}
+
+impl TaskChanges {
+    pub fn subscribe(&self) -> broadcast::Receiver<DateTime<Utc>> {
+        self.sender.subscribe()
+    }
+
+    pub fn notify(&self) {
+        // no subscribers is fine, nobody has to be told.
+        let _ = self.sender.send(Utc::now());
+    }
+}
+
+fn is_database_change(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|p| {
+        p.file_name()
+            .and_then(|p| p.to_str())
+            .is_some_and(|p| DATABASE_FILES.contains(&p))
+    })
+}
+
+/// Starts watching the task database, changes are published through `AppState::task_changes`.
+///
+/// # Errors
+///
+/// Will return error if the folder of the task database cannot be watched
+pub fn spawn_task_db_watcher(app_state: &AppState) -> Result<JoinHandle<()>, anyhow::Error> {
+    let taskdb = app_state.task_storage_path.clone();
+    let task_changes = app_state.task_changes.clone();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if is_database_change(&event) => {
+            let _ = tx.send(());
+        }
+        Ok(_) => {}
+        Err(e) => error!("Watching the task database failed: {:?}", e),
+    })?;
+    // The database itself is created on first use, so its folder is watched.
+    std::fs::create_dir_all(&taskdb)?;
+    watcher.watch(&taskdb, RecursiveMode::NonRecursive)?;
+    info!("Watching {:?} for changes of the tasks", taskdb);
+    Ok(tokio::spawn(async move {
+        // dropping the watcher stops it.
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE_DELAY).await;
+            while rx.try_recv().is_ok() {}
+            task_changes.notify();
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests;
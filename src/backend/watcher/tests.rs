@@ -0,0 +1,57 @@
+/*
+ * Copyright 2026 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use notify::event::{AccessKind, CreateKind, ModifyKind};
+use taskchampion::{Operations, Status, Uuid};
+
+use super::*;
+
+#[test]
+fn test_is_database_change() {
+    let event = |kind: EventKind, file: &str| Event::new(kind).add_path(PathBuf::from(file));
+    assert!(is_database_change(&event(
+        EventKind::Modify(ModifyKind::Any),
+        "/tmp/task/taskchampion.sqlite3-wal"
+    )));
+    assert!(is_database_change(&event(
+        EventKind::Create(CreateKind::File),
+        "/tmp/task/taskchampion.sqlite3"
+    )));
+    assert!(!is_database_change(&event(
+        EventKind::Access(AccessKind::Any),
+        "/tmp/task/taskchampion.sqlite3"
+    )));
+    assert!(!is_database_change(&event(
+        EventKind::Modify(ModifyKind::Any),
+        "/tmp/task/taskchampion.sqlite3-shm"
+    )));
+}
+
+#[tokio::test]
+async fn test_task_db_watcher() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let watcher = spawn_task_db_watcher(&app_state)?;
+    let mut changes = app_state.task_changes.subscribe();
+
+    let mut replica = app_state.replica.write().await?;
+    let mut ops = Operations::new();
+    let mut task = replica.create_task(Uuid::new_v4(), &mut ops).await?;
+    task.set_description("Watched task".into(), &mut ops)?;
+    task.set_status(Status::Pending, &mut ops)?;
+    replica.commit_operations(ops).await?;
+    drop(replica);
+
+    tokio::time::timeout(Duration::from_secs(5), changes.recv()).await??;
+    watcher.abort();
+    Ok(())
+}
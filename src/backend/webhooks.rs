@@ -0,0 +1,253 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Outgoing HTTP webhooks.
+//!
+//! Every `[[webhooks]]` of the configuration gets a POST request with the old and the
+//! new task as JSON after a task was added, modified, completed or deleted:
+//!
+//! ```json
+//! {"event": "complete", "time": "2025-05-01T10:00:00Z", "old": {...}, "new": {...}}
+//! ```
+//!
+//! `old` is `null` for added tasks. With a secret, the body is signed with HMAC-SHA256
+//! in the `X-Twk-Signature` header as `sha256=<hex digest>`.
+//!
+//! Requests are sent in the background by [`spawn_webhook_worker`]. A request failing
+//! or not answered with a 2xx status is retried with an increasing delay,
+//! up to [`MAX_WEBHOOK_ATTEMPTS`] times.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use taskchampion::{Status, Uuid};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use super::task::Task;
+use crate::core::app::AppState;
+use crate::core::config::{WebhookEvent, WebhookSettings};
+use crate::endpoints::tasks::task_filter::TaskFilter;
+
+/// Number of times a request is sent before it is given up.
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 5;
+/// Time to wait for the response of a webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delay before the first retry, doubled on every further attempt.
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+pub const SIGNATURE_HEADER: &str = "X-Twk-Signature";
+pub const EVENT_HEADER: &str = "X-Twk-Event";
+pub const DELIVERY_HEADER: &str = "X-Twk-Delivery";
+
+/// Body of a webhook request.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub event: WebhookEvent,
+    pub time: DateTime<Utc>,
+    pub old: Option<&'a Task>,
+    pub new: &'a Task,
+}
+
+/// Pending request of a webhook.
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook: WebhookSettings,
+    pub event: WebhookEvent,
+    pub body: String,
+    /// Number of times the request was sent already.
+    pub attempts: u32,
+}
+
+impl WebhookDelivery {
+    /// Signature of the body, if the webhook has a secret.
+    pub fn signature(&self) -> Option<String> {
+        let secret = self.webhook.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+        mac.update(self.body.as_bytes());
+        Some(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+}
+
+/// Queue of the webhook requests, shared through the `AppState`.
+/// The requests are sent by the worker started with [`spawn_webhook_worker`].
+#[derive(Clone)]
+pub struct WebhookQueue {
+    sender: UnboundedSender<WebhookDelivery>,
+    receiver: Arc<Mutex<Option<UnboundedReceiver<WebhookDelivery>>>>,
+    retry_delay: Duration,
+}
+
+impl Default for WebhookQueue {
+    fn default() -> Self {
+        Self::new(WEBHOOK_RETRY_DELAY)
+    }
+}
+
+impl WebhookQueue {
+    pub fn new(retry_delay: Duration) -> Self {
+        let (sender, receiver) = unbounded_channel();
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(Some(receiver))),
+            retry_delay,
+        }
+    }
+
+    pub fn push(&self, delivery: WebhookDelivery) {
+        if let Err(e) = self.sender.send(delivery) {
+            error!("Could not queue webhook request {}", e.0.id);
+        }
+    }
+
+    /// Delay before the request is sent again,
+    /// doubled with every failed attempt.
+    pub fn retry_delay(&self, attempts: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2_u32.saturating_pow(attempts.saturating_sub(1).min(16)))
+    }
+
+    fn take_receiver(&self) -> Option<UnboundedReceiver<WebhookDelivery>> {
+        self.receiver.lock().ok()?.take()
+    }
+}
+
+/// Kind of change between the old and the new task.
+pub fn webhook_event(old: Option<&Task>, new: &Task) -> WebhookEvent {
+    let Some(old) = old else {
+        return WebhookEvent::Add;
+    };
+    match new.status {
+        Some(Status::Completed) if old.status != Some(Status::Completed) => WebhookEvent::Complete,
+        Some(Status::Deleted) if old.status != Some(Status::Deleted) => WebhookEvent::Delete,
+        _ => WebhookEvent::Modify,
+    }
+}
+
+/// Queues the requests of all webhooks interested in the change of the task.
+pub fn queue_webhooks(app_state: &AppState, old: Option<&Task>, new: &Task) {
+    let webhooks = &app_state.app_config.webhooks;
+    if webhooks.is_empty() {
+        return;
+    }
+    let event = webhook_event(old, new);
+    let now = Utc::now();
+    let body = match serde_json::to_string(&WebhookPayload {
+        event,
+        time: now,
+        old,
+        new,
+    }) {
+        Ok(p) => p,
+        Err(e) => {
+            error!("Could not serialize task {} for webhooks: {}", new.uuid, e);
+            return;
+        }
+    };
+    for webhook in webhooks.iter().filter(|p| p.fires_on(event)) {
+        // filters were validated with the settings.
+        if let Some(filter) = &webhook.filter
+            && !TaskFilter::parse(filter).is_ok_and(|p| p.matches(new, now))
+        {
+            continue;
+        }
+        app_state.webhooks.push(WebhookDelivery {
+            id: Uuid::new_v4(),
+            webhook: webhook.clone(),
+            event,
+            body: body.clone(),
+            attempts: 0,
+        });
+    }
+}
+
+/// Sends the request of the delivery once.
+pub async fn send_webhook(
+    client: &reqwest::Client,
+    delivery: &WebhookDelivery,
+) -> Result<(), anyhow::Error> {
+    let mut request = client
+        .post(&delivery.webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, delivery.event.to_string())
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .body(delivery.body.clone());
+    if let Some(signature) = delivery.signature() {
+        request = request.header(SIGNATURE_HEADER, signature);
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Starts the background task sending the queued webhook requests.
+/// Nothing is started if no webhooks are configured.
+pub fn spawn_webhook_worker(app_state: &AppState) -> Option<JoinHandle<()>> {
+    if app_state.app_config.webhooks.is_empty() {
+        return None;
+    }
+    let queue = app_state.webhooks.clone();
+    let Some(mut receiver) = queue.take_receiver() else {
+        warn!("The webhook worker is running already");
+        return None;
+    };
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(p) => p,
+        Err(e) => {
+            error!("Could not create the client for webhooks: {}", e);
+            return None;
+        }
+    };
+    info!(
+        "Sending task changes to {} webhooks",
+        app_state.app_config.webhooks.len()
+    );
+    Some(tokio::spawn(async move {
+        while let Some(mut delivery) = receiver.recv().await {
+            delivery.attempts += 1;
+            match send_webhook(&client, &delivery).await {
+                Ok(()) => debug!(
+                    "Webhook {} received request {}",
+                    delivery.webhook.url, delivery.id
+                ),
+                Err(e) if delivery.attempts >= MAX_WEBHOOK_ATTEMPTS => error!(
+                    "Webhook {} failed {} times, giving up request {}: {}",
+                    delivery.webhook.url, delivery.attempts, delivery.id, e
+                ),
+                Err(e) => {
+                    let delay = queue.retry_delay(delivery.attempts);
+                    warn!(
+                        "Webhook {} failed, retrying request {} in {} seconds: {}",
+                        delivery.webhook.url,
+                        delivery.id,
+                        delay.as_secs(),
+                        e
+                    );
+                    let queue = queue.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        queue.push(delivery);
+                    });
+                }
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests;
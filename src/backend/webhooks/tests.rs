@@ -0,0 +1,240 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use taskchampion::Status;
+
+use super::{
+    DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, WebhookQueue, spawn_webhook_worker,
+    webhook_event,
+};
+use crate::backend::task::Task;
+use crate::core::app::AppState;
+use crate::core::config::{AppSettings, WebhookEvent, WebhookSettings};
+use crate::endpoints::tasks::{change_task_status, run_modify_command, task_add, task_undo};
+use crate::{NewTask, TaskUpdateStatus, get_random_appstate};
+
+#[derive(Clone, Default)]
+struct Listener {
+    requests: Arc<Mutex<Vec<(HeaderMap, Value)>>>,
+    /// Number of requests answered with an error before accepting them.
+    failures: Arc<AtomicUsize>,
+}
+
+impl Listener {
+    /// Starts the HTTP listener, returns its url.
+    async fn start(&self) -> anyhow::Result<String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(self.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Ok(url)
+    }
+
+    /// Waits until `count` requests were received.
+    async fn wait_for(&self, count: usize) -> Vec<(HeaderMap, Value)> {
+        for _ in 0..100 {
+            if self.requests.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn receive(State(listener): State<Listener>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let failures = listener.failures.load(Ordering::SeqCst);
+    if failures > 0 {
+        listener.failures.store(failures - 1, Ordering::SeqCst);
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"shared").unwrap();
+    mac.update(&body);
+    let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+    assert_eq!(
+        headers.get(SIGNATURE_HEADER).and_then(|p| p.to_str().ok()),
+        Some(signature.as_str())
+    );
+    let body = serde_json::from_slice(&body).unwrap();
+    listener.requests.lock().unwrap().push((headers, body));
+    StatusCode::NO_CONTENT
+}
+
+fn with_webhook(app_state: &mut AppState, url: String, events: Vec<WebhookEvent>) {
+    app_state.app_config = Arc::new(AppSettings {
+        webhooks: vec![WebhookSettings {
+            url,
+            events,
+            filter: Some(String::from("+work")),
+            secret: Some(String::from("shared")),
+        }],
+        ..AppSettings::default()
+    });
+    app_state.webhooks = WebhookQueue::new(Duration::from_millis(10));
+}
+
+#[test]
+fn test_webhook_event() {
+    let old = Task {
+        status: Some(Status::Pending),
+        ..Task::default()
+    };
+    let mut new = old.clone();
+    assert_eq!(webhook_event(None, &new), WebhookEvent::Add);
+    assert_eq!(webhook_event(Some(&old), &new), WebhookEvent::Modify);
+    new.status = Some(Status::Completed);
+    assert_eq!(webhook_event(Some(&old), &new), WebhookEvent::Complete);
+    // changing a completed task is no new completion.
+    assert_eq!(webhook_event(Some(&new), &new), WebhookEvent::Modify);
+    new.status = Some(Status::Deleted);
+    assert_eq!(webhook_event(Some(&old), &new), WebhookEvent::Delete);
+}
+
+#[tokio::test]
+async fn test_webhooks_post_task_changes() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = get_random_appstate();
+    let listener = Listener::default();
+    let url = listener.start().await?;
+    with_webhook(
+        &mut app_state,
+        url,
+        vec![WebhookEvent::Add, WebhookEvent::Complete],
+    );
+    assert!(spawn_webhook_worker(&app_state).is_some());
+
+    let uuid = task_add(
+        &NewTask::new(
+            Some("Write the report".into()),
+            Some("+work".into()),
+            None,
+            None,
+            None,
+        ),
+        &app_state,
+    )
+    .await
+    .unwrap();
+    // filtered out.
+    task_add(
+        &NewTask::new(Some("Water the plants".into()), None, None, None, None),
+        &app_state,
+    )
+    .await
+    .unwrap();
+    // no modify event configured.
+    run_modify_command(uuid, "priority:H", &app_state)
+        .await
+        .unwrap();
+    change_task_status(
+        TaskUpdateStatus {
+            status: String::from("completed"),
+            uuid,
+        },
+        &app_state,
+    )
+    .await?;
+
+    let requests = listener.wait_for(2).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(listener.requests.lock().unwrap().len(), 2);
+    let (headers, added) = &requests[0];
+    assert_eq!(headers.get(EVENT_HEADER).unwrap(), "add");
+    assert_eq!(added["event"], "add");
+    assert_eq!(added["old"], Value::Null);
+    assert_eq!(added["new"]["description"], "Write the report");
+    let (headers, completed) = &requests[1];
+    assert_eq!(headers.get(EVENT_HEADER).unwrap(), "complete");
+    assert_eq!(completed["old"]["status"], "pending");
+    assert_eq!(completed["old"]["priority"], "H");
+    assert_eq!(completed["new"]["status"], "completed");
+    assert_eq!(completed["new"]["uuid"], uuid.to_string());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_webhooks_post_undone_changes() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = get_random_appstate();
+    let listener = Listener::default();
+    let url = listener.start().await?;
+    with_webhook(&mut app_state, url, vec![WebhookEvent::Modify]);
+    assert!(spawn_webhook_worker(&app_state).is_some());
+
+    let uuid = task_add(
+        &NewTask::new(
+            Some("Write the report".into()),
+            Some("+work".into()),
+            None,
+            None,
+            None,
+        ),
+        &app_state,
+    )
+    .await
+    .unwrap();
+    run_modify_command(uuid, "priority:H", &app_state)
+        .await
+        .unwrap();
+    assert_eq!(task_undo(1, &app_state).await?, 1);
+
+    let requests = listener.wait_for(2).await;
+    assert_eq!(requests.len(), 2);
+    let (headers, undone) = &requests[1];
+    assert_eq!(headers.get(EVENT_HEADER).unwrap(), "modify");
+    assert_eq!(undone["old"]["priority"], "H");
+    assert_ne!(undone["new"]["priority"], "H");
+    assert_eq!(undone["new"]["uuid"], uuid.to_string());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_webhooks_retry() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = get_random_appstate();
+    let listener = Listener::default();
+    listener.failures.store(2, Ordering::SeqCst);
+    let url = listener.start().await?;
+    with_webhook(&mut app_state, url, vec![]);
+    spawn_webhook_worker(&app_state);
+    // the worker only runs once.
+    assert!(spawn_webhook_worker(&app_state).is_none());
+
+    task_add(
+        &NewTask::new(
+            Some("Write the report".into()),
+            Some("+work".into()),
+            None,
+            None,
+            None,
+        ),
+        &app_state,
+    )
+    .await
+    .unwrap();
+
+    let requests = listener.wait_for(1).await;
+    assert_eq!(requests.len(), 1);
+    assert_eq!(listener.failures.load(Ordering::SeqCst), 0);
+    assert!(requests[0].0.contains_key(DELIVERY_HEADER));
+    assert_eq!(requests[0].1["event"], "add");
+    Ok(())
+}
@@ -8,6 +8,8 @@
  * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+use axum::extract::FromRef;
+use axum_extra::extract::cookie::Key;
 use directories::ProjectDirs;
 use std::{
     env::{self, home_dir},
@@ -19,9 +21,19 @@ use std::{
 use tera::Context;
 use tracing::info;
 
+use crate::backend::api_tokens::ApiTokenStore;
+use crate::backend::auth::session_key;
+use crate::backend::hooks::HookLog;
+use crate::backend::replica::ReplicaPool;
+use crate::backend::sync::SyncState;
+use crate::backend::sync_server::SyncServerStorage;
+use crate::backend::watcher::TaskChanges;
+use crate::backend::webhooks::WebhookQueue;
+
 use super::{
     cache::{FileMnemonicsCache, MnemonicsCacheType},
     config::AppSettings,
+    taskrc::Taskrc,
 };
 
 /// Holds state information and configurations
@@ -36,6 +48,7 @@ use super::{
 /// | TWK_THEME                 | theme                    |
 /// | DISPLAY_TIME_OF_THE_DAY   | display_time_of_the_day  |
 /// | TASKDATA                  | task_storage_path        |
+/// | TASKRC                    | task_hooks_path          |
 /// | TWK_CONFIG_FOLDER         | app_config_path          |
 /// | TWK_SYNC                  | interval in seconds      |
 ///
@@ -46,12 +59,24 @@ pub struct AppState {
     pub theme: Option<String>,
     pub display_time_of_the_day: i32,
     pub task_storage_path: PathBuf,
+    /// Replicas of the task database in `task_storage_path`.
+    pub replica: ReplicaPool,
     pub task_hooks_path: Option<PathBuf>,
+    /// Recent invocations of the hooks in `task_hooks_path`.
+    pub hook_log: HookLog,
+    /// Pending requests of the webhooks in `app_config`.
+    pub webhooks: WebhookQueue,
     pub app_config_path: PathBuf,
     pub app_cache_path: PathBuf,
     pub app_cache: Arc<RwLock<MnemonicsCacheType>>,
     pub app_config: Arc<AppSettings>,
     pub sync_interval: i64,
+    pub sync_state: SyncState,
+    pub sync_server: SyncServerStorage,
+    pub task_changes: TaskChanges,
+    /// Signs the session cookies of the users in `app_config`.
+    pub session_key: Key,
+    pub api_tokens: ApiTokenStore,
     // Here must be cache object for mnemonics
 }
 
@@ -76,7 +101,6 @@ impl Default for AppState {
 
         let task_storage_path =
             PathBuf::from_str(&task_storage_path).expect("Storage path cannot be found");
-        let task_hooks_path = Some(home_dir.join("hooks"));
 
         let standard_project_dirs = ProjectDirs::from("", "", "Taskwarrior-Web");
 
@@ -108,6 +132,27 @@ impl Default for AppState {
         }
         .expect("Proper configuration file does not exist");
 
+        let task_hooks_path = app_settings
+            .hooks
+            .hooks_path(Taskrc::load().as_ref(), &task_storage_path);
+        match &task_hooks_path {
+            Some(p) => info!("Hooks are run from {:?}", p),
+            None => info!("Hooks are disabled"),
+        }
+
+        // Versions of the embedded sync server live next to the configuration.
+        let sync_server = SyncServerStorage::new(
+            app_config_path
+                .parent()
+                .map_or_else(|| PathBuf::from("sync-server"), |p| p.join("sync-server")),
+            app_settings.sync_server.snapshot_versions,
+        );
+
+        let api_tokens = ApiTokenStore::new(app_config_path.parent().map_or_else(
+            || PathBuf::from("api_tokens.json"),
+            |p| p.join("api_tokens.json"),
+        ));
+
         // Overall determination of the cache folder.
         let app_cache_path = standard_project_dirs
             .map(|p| p.cache_dir().to_path_buf())
@@ -136,22 +181,38 @@ impl Default for AppState {
         // For this, we need also to ensure, that conflicting cache entries are removed!
         app_settings.register_shortcuts(&mut cache);
 
+        let session_key = session_key(&app_settings.auth);
+
         Self {
             font,
             fallback_family: "monospace".to_string(),
             theme,
             display_time_of_the_day,
+            replica: ReplicaPool::new(task_storage_path.clone()),
             task_storage_path,
             task_hooks_path,
+            hook_log: HookLog::default(),
+            webhooks: WebhookQueue::default(),
             app_config_path,
             app_cache_path,
             app_cache: Arc::new(RwLock::new(cache)),
             app_config: Arc::new(app_settings),
             sync_interval,
+            sync_state: SyncState::default(),
+            sync_server,
+            task_changes: TaskChanges::default(),
+            session_key,
+            api_tokens,
         }
     }
 }
 
+impl FromRef<AppState> for Key {
+    fn from_ref(state: &AppState) -> Self {
+        state.session_key.clone()
+    }
+}
+
 impl From<&AppState> for Context {
     fn from(val: &AppState) -> Self {
         let mut ctx = Self::new();
@@ -159,6 +220,9 @@ impl From<&AppState> for Context {
         ctx.insert("FALLBACK_FAMILY", &val.fallback_family);
         ctx.insert("DEFAULT_THEME", &val.theme);
         ctx.insert("display_time_of_the_day", &val.display_time_of_the_day);
+        ctx.insert("sync_enabled", &val.app_config.sync.is_some());
+        ctx.insert("sync_status", &val.sync_state.status());
+        ctx.insert("auth_enabled", &val.app_config.auth.enabled());
         ctx
     }
 }
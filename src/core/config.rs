@@ -9,11 +9,17 @@
  */
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use taskchampion::{ServerConfig, Uuid};
 
 use super::{
     cache::{MnemonicsCache, MnemonicsType},
     errors::FieldError,
+    taskrc::Taskrc,
 };
+use crate::backend::urgency::UrgencyCoefficients;
+use crate::endpoints::tasks::task_filter::TaskFilter;
 
 pub trait ValidateSetting {
     fn validate(&self) -> Vec<FieldError>;
@@ -26,10 +32,247 @@ pub struct CustomQuery {
     pub fixed_key: Option<String>,
 }
 
+/// Directory of a local sync server, like `sync.local.server_dir` in taskrc.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LocalSyncServer {
+    pub server_dir: PathBuf,
+}
+
+/// Remote taskchampion sync server, like `sync.server.*` in taskrc.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteSyncServer {
+    pub url: String,
+    pub client_id: String,
+}
+
+/// Server the replica is synchronized with.
+/// Exactly one of `local` and `server` has to be given,
+/// the remote server requires the `encryption_secret`.
+#[derive(serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncSettings {
+    pub local: Option<LocalSyncServer>,
+    pub server: Option<RemoteSyncServer>,
+    pub encryption_secret: Option<String>,
+    /// Do not upload snapshots, even if the server asks for them.
+    #[serde(default)]
+    pub avoid_snapshots: bool,
+    /// Seconds to wait for an answer of the sync server, 30 if not given.
+    pub request_timeout: Option<u64>,
+}
+
+impl SyncSettings {
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.request_timeout.unwrap_or(30))
+    }
+
+    /// Builds the taskchampion configuration of the sync server.
+    pub fn server_config(&self) -> Result<ServerConfig, anyhow::Error> {
+        match (&self.local, &self.server) {
+            (Some(local), None) => Ok(ServerConfig::Local {
+                server_dir: local.server_dir.clone(),
+            }),
+            (None, Some(server)) => {
+                let Some(encryption_secret) = self.encryption_secret.as_ref() else {
+                    anyhow::bail!("A sync server requires an encryption secret");
+                };
+                Ok(ServerConfig::Remote {
+                    url: server.url.clone(),
+                    client_id: Uuid::parse_str(&server.client_id)?,
+                    encryption_secret: encryption_secret.as_bytes().to_vec(),
+                })
+            }
+            (Some(_), Some(_)) => {
+                anyhow::bail!("Either a local or a remote sync server can be used")
+            }
+            (None, None) => anyhow::bail!("No sync server configured"),
+        }
+    }
+}
+
+/// Embedded taskchampion sync server, other replicas can sync against.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct SyncServerSettings {
+    pub enabled: bool,
+    /// Client ids allowed to sync, no client is accepted if empty.
+    pub client_ids: Vec<String>,
+    /// Number of versions after which clients are asked for a snapshot.
+    pub snapshot_versions: u64,
+}
+
+impl Default for SyncServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            client_ids: Vec::new(),
+            snapshot_versions: 100,
+        }
+    }
+}
+
+impl SyncServerSettings {
+    pub fn allows_client(&self, client_id: Uuid) -> bool {
+        self.client_ids
+            .iter()
+            .any(|p| Uuid::parse_str(p).is_ok_and(|p| p == client_id))
+    }
+}
+
+/// Execution of the Taskwarrior hook scripts.
+/// `enabled` and `location` override `hooks` and `hooks.location` of the taskrc.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct HookSettings {
+    pub enabled: Option<bool>,
+    pub location: Option<PathBuf>,
+    /// Seconds a hook may run before it is killed.
+    pub timeout: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: None,
+            location: None,
+            timeout: 10,
+        }
+    }
+}
+
+impl HookSettings {
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout)
+    }
+
+    /// Folder of the hooks, `None` if hooks are disabled.
+    /// The settings win over the taskrc, without either the hooks are
+    /// in the `hooks` folder of the task database, like Taskwarrior does.
+    pub fn hooks_path(&self, taskrc: Option<&Taskrc>, task_storage_path: &Path) -> Option<PathBuf> {
+        let enabled = self
+            .enabled
+            .or_else(|| taskrc.and_then(|p| p.get_bool("hooks")))
+            .unwrap_or(true);
+        if !enabled {
+            return None;
+        }
+        self.location
+            .clone()
+            .or_else(|| taskrc.and_then(|p| p.get_path("hooks.location")))
+            .or_else(|| Some(task_storage_path.join("hooks")))
+    }
+}
+
+/// Change of a task a webhook is called for.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEvent {
+    Add,
+    Modify,
+    Complete,
+    Delete,
+}
+
+/// HTTP endpoint the changed tasks are posted to.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WebhookSettings {
+    pub url: String,
+    /// Events the webhook is called for, all of them if empty.
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// Only tasks matching the filter are posted, like `+work project:Home`.
+    pub filter: Option<String>,
+    /// Signs the body with HMAC-SHA256, sent in the `X-Twk-Signature` header.
+    pub secret: Option<String>,
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Add => "add",
+            Self::Modify => "modify",
+            Self::Complete => "complete",
+            Self::Delete => "delete",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl WebhookSettings {
+    pub fn fires_on(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Local user allowed to log in.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuthUser {
+    pub username: String,
+    /// Argon2 hash of the password, as printed by `taskwarrior-web hash-password`.
+    pub password_hash: String,
+}
+
+/// Login for the web UI and the API, disabled without users.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct AuthSettings {
+    pub users: Vec<AuthUser>,
+    /// Key the session cookies are signed with.
+    /// Without it a random key is used and all sessions end with a restart.
+    pub session_secret: Option<String>,
+    /// Hours a login lasts.
+    pub session_hours: u64,
+    /// Sends the session cookie over https only.
+    pub secure_cookie: bool,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        Self {
+            users: Vec::new(),
+            session_secret: None,
+            session_hours: 24 * 7,
+            secure_cookie: false,
+        }
+    }
+}
+
+impl AuthSettings {
+    pub fn enabled(&self) -> bool {
+        !self.users.is_empty()
+    }
+
+    pub fn user(&self, username: &str) -> Option<&AuthUser> {
+        self.users.iter().find(|p| p.username == username)
+    }
+
+    pub fn session_duration(&self) -> chrono::Duration {
+        i64::try_from(self.session_hours)
+            .ok()
+            .and_then(chrono::Duration::try_hours)
+            .unwrap_or(chrono::Duration::MAX)
+    }
+}
+
 #[derive(serde::Deserialize, Clone, Debug, Default)]
 pub struct AppSettings {
     #[serde(default)]
     pub custom_queries: HashMap<String, CustomQuery>,
+    /// Read task lists via `task export` instead of the taskchampion replica.
+    #[serde(default)]
+    pub use_task_cli: bool,
+    /// Coefficients for the urgency, like `urgency.*.coefficient` in taskrc.
+    #[serde(default)]
+    pub urgency: UrgencyCoefficients,
+    /// Server to synchronize the replica with, synchronization is disabled without.
+    pub sync: Option<SyncSettings>,
+    #[serde(default)]
+    pub sync_server: SyncServerSettings,
+    #[serde(default)]
+    pub hooks: HookSettings,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookSettings>,
+    #[serde(default)]
+    pub auth: AuthSettings,
 }
 
 impl AppSettings {
@@ -82,7 +325,7 @@ impl ValidateSetting for CustomQuery {
                 message: format!(
                     "Fixed key must be 2 unique characters. Currently assigned {:?} for {}!",
                     self.fixed_key.as_ref(),
-                    &self.description
+                    self.description
                 ),
             });
         }
@@ -120,9 +363,260 @@ impl<S: ::std::hash::BuildHasher> ValidateSetting for HashMap<String, CustomQuer
     }
 }
 
+impl ValidateSetting for UrgencyCoefficients {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        let mut check_coefficient = |field: String, coefficient: f64| {
+            if !coefficient.is_finite() {
+                errors.push(FieldError {
+                    message: format!(
+                        "Urgency coefficient {field} must be a number, got {coefficient}"
+                    ),
+                    field,
+                });
+            }
+        };
+        for (name, coefficient) in [
+            ("due", self.due),
+            ("blocking", self.blocking),
+            ("scheduled", self.scheduled),
+            ("active", self.active),
+            ("age", self.age),
+            ("annotations", self.annotations),
+            ("tags", self.tags),
+            ("project", self.project),
+            ("waiting", self.waiting),
+            ("blocked", self.blocked),
+        ] {
+            check_coefficient(format!("urgency.{name}"), coefficient);
+        }
+        for (group, coefficients) in [
+            ("user_tag", &self.user_tag),
+            ("user_project", &self.user_project),
+            ("uda", &self.uda),
+        ] {
+            for (name, coefficient) in coefficients {
+                check_coefficient(format!("urgency.{group}.{name}"), *coefficient);
+            }
+        }
+
+        if !self.age_max.is_finite() || self.age_max < 0.0 {
+            errors.push(FieldError {
+                field: String::from("urgency.age_max"),
+                message: format!(
+                    "Urgency age_max must be a positive number of days, got {}",
+                    self.age_max
+                ),
+            });
+        }
+        for (group, coefficients) in [
+            ("user_tag", &self.user_tag),
+            ("user_project", &self.user_project),
+        ] {
+            if coefficients.keys().any(|p| p.trim().is_empty()) {
+                errors.push(FieldError {
+                    field: format!("urgency.{group}"),
+                    message: format!("Urgency {group} coefficients need a name"),
+                });
+            }
+        }
+        for name in self.uda.keys() {
+            let valid = match name.split_once('.') {
+                Some((uda, value)) => !uda.is_empty() && !value.is_empty(),
+                None => !name.is_empty(),
+            };
+            if !valid {
+                errors.push(FieldError {
+                    field: format!("urgency.uda.{name}"),
+                    message: format!(
+                        "Urgency uda coefficient {name:?} must be given as `name` or `name.value`"
+                    ),
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+impl ValidateSetting for SyncSettings {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        if let Err(e) = self.server_config() {
+            errors.push(FieldError {
+                field: String::from("sync"),
+                message: e.to_string(),
+            });
+        }
+        if let Some(server) = &self.server
+            && !(server.url.starts_with("http://") || server.url.starts_with("https://"))
+        {
+            errors.push(FieldError {
+                field: String::from("sync.server.url"),
+                message: format!("Sync server url must be a http(s) url, got {}", server.url),
+            });
+        }
+        if self
+            .encryption_secret
+            .as_ref()
+            .is_some_and(String::is_empty)
+        {
+            errors.push(FieldError {
+                field: String::from("sync.encryption_secret"),
+                message: String::from("Sync encryption secret must not be empty"),
+            });
+        }
+        if self.request_timeout == Some(0) {
+            errors.push(FieldError {
+                field: String::from("sync.request_timeout"),
+                message: String::from("Sync request_timeout must be at least 1 second"),
+            });
+        }
+
+        errors
+    }
+}
+
+impl ValidateSetting for SyncServerSettings {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        if self.enabled && self.client_ids.is_empty() {
+            errors.push(FieldError {
+                field: String::from("sync_server.client_ids"),
+                message: String::from("Sync server needs at least one allowed client id"),
+            });
+        }
+        for client_id in &self.client_ids {
+            if Uuid::parse_str(client_id).is_err() {
+                errors.push(FieldError {
+                    field: String::from("sync_server.client_ids"),
+                    message: format!("Sync server client id {client_id} is not a valid uuid"),
+                });
+            }
+        }
+        if self.snapshot_versions == 0 {
+            errors.push(FieldError {
+                field: String::from("sync_server.snapshot_versions"),
+                message: String::from("Sync server snapshot_versions must be at least 1"),
+            });
+        }
+
+        errors
+    }
+}
+
+impl ValidateSetting for HookSettings {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        if self.timeout == 0 {
+            errors.push(FieldError {
+                field: String::from("hooks.timeout"),
+                message: String::from("Hook timeout must be at least 1 second"),
+            });
+        }
+
+        errors
+    }
+}
+
+impl ValidateSetting for WebhookSettings {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            errors.push(FieldError {
+                field: String::from("webhooks.url"),
+                message: format!(
+                    "Webhook url {} must start with http:// or https://",
+                    self.url
+                ),
+            });
+        }
+        if let Some(filter) = &self.filter
+            && let Err(e) = TaskFilter::parse(filter)
+        {
+            errors.push(FieldError {
+                field: String::from("webhooks.filter"),
+                message: format!("Webhook filter {filter} is invalid: {e}"),
+            });
+        }
+        if self.secret.as_ref().is_some_and(String::is_empty) {
+            errors.push(FieldError {
+                field: String::from("webhooks.secret"),
+                message: String::from("Webhook secret must not be empty"),
+            });
+        }
+
+        errors
+    }
+}
+
+impl ValidateSetting for AuthSettings {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors: Vec<FieldError> = Vec::new();
+        let mut usernames: Vec<&str> = Vec::new();
+        for user in &self.users {
+            if user.username.is_empty()
+                || !user
+                    .username
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "._-@".contains(c))
+            {
+                errors.push(FieldError {
+                    field: String::from("auth.users.username"),
+                    message: format!(
+                        "Username {:?} must consist of letters, digits and ._-@",
+                        user.username
+                    ),
+                });
+            } else if usernames.contains(&user.username.as_str()) {
+                errors.push(FieldError {
+                    field: String::from("auth.users.username"),
+                    message: format!("Duplicate user {}", user.username),
+                });
+            } else {
+                usernames.push(&user.username);
+            }
+            if let Err(e) = argon2::PasswordHash::new(&user.password_hash) {
+                errors.push(FieldError {
+                    field: String::from("auth.users.password_hash"),
+                    message: format!(
+                        "Password hash of user {} is not an argon2 hash: {e}",
+                        user.username
+                    ),
+                });
+            }
+        }
+        if self.session_secret.as_ref().is_some_and(String::is_empty) {
+            errors.push(FieldError {
+                field: String::from("auth.session_secret"),
+                message: String::from("Session secret must not be empty"),
+            });
+        }
+        if self.session_hours == 0 {
+            errors.push(FieldError {
+                field: String::from("auth.session_hours"),
+                message: String::from("Sessions must last at least 1 hour"),
+            });
+        }
+
+        errors
+    }
+}
+
 impl ValidateSetting for AppSettings {
     fn validate(&self) -> Vec<FieldError> {
-        self.custom_queries.validate()
+        let mut errors = self.custom_queries.validate();
+        errors.extend(self.urgency.validate());
+        if let Some(sync) = &self.sync {
+            errors.extend(sync.validate());
+        }
+        errors.extend(self.sync_server.validate());
+        errors.extend(self.hooks.validate());
+        for webhook in &self.webhooks {
+            errors.extend(webhook.validate());
+        }
+        errors.extend(self.auth.validate());
+        errors
     }
 }
 
@@ -239,6 +733,84 @@ mod tests {
         assert_eq!(valid.len(), 2);
     }
 
+    #[test]
+    fn test_config_sync() {
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let data = String::from(
+            "[sync]\nencryption_secret = \"secret\"\n\n[sync.server]\nurl = \"https://sync.example.com\"\nclient_id = \"9f5d7b1e-3c6a-4b8e-9d2f-0a1b2c3d4e5f\"\n",
+        );
+        let _ = file1.write_all(data.as_bytes());
+        let _ = file1.flush();
+
+        let appconf = AppSettings::new(file1.path()).unwrap();
+        let sync = appconf.sync.unwrap();
+        assert_eq!(
+            sync.server.as_ref().unwrap().url,
+            "https://sync.example.com"
+        );
+        assert!(!sync.avoid_snapshots);
+        assert!(matches!(
+            sync.server_config(),
+            Ok(ServerConfig::Remote { .. })
+        ));
+        assert!(AppSettings::default().sync.is_none());
+    }
+
+    #[test]
+    fn test_config_sync_validation() {
+        let local = SyncSettings {
+            local: Some(LocalSyncServer {
+                server_dir: PathBuf::from("/tmp/sync"),
+            }),
+            ..SyncSettings::default()
+        };
+        assert!(local.validate().is_empty());
+        let no_timeout = SyncSettings {
+            request_timeout: Some(0),
+            ..local.clone()
+        };
+        assert_eq!(no_timeout.validate()[0].field, "sync.request_timeout");
+
+        let remote = SyncSettings {
+            server: Some(RemoteSyncServer {
+                url: String::from("sync.example.com"),
+                client_id: String::from("not-a-uuid"),
+            }),
+            encryption_secret: Some(String::new()),
+            ..SyncSettings::default()
+        };
+        let errors = remote.validate();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|p| p.field == "sync.server.url"));
+        assert!(errors.iter().any(|p| p.field == "sync.encryption_secret"));
+
+        let both = SyncSettings {
+            server: remote.server.clone(),
+            ..local.clone()
+        };
+        assert_eq!(both.validate()[0].field, "sync");
+        assert_eq!(SyncSettings::default().validate().len(), 1);
+    }
+
+    #[test]
+    fn test_config_sync_server_validation() {
+        let client_id = Uuid::new_v4();
+        let mut server = SyncServerSettings::default();
+        assert!(server.validate().is_empty());
+        assert!(!server.allows_client(client_id));
+
+        server.enabled = true;
+        let errors = server.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "sync_server.client_ids");
+
+        server.client_ids = vec![client_id.to_string()];
+        assert!(server.validate().is_empty());
+        assert!(server.allows_client(client_id));
+        assert!(!server.allows_client(Uuid::new_v4()));
+    }
+
     #[test]
     fn test_config_register_shortcut() {
         let mut appconf = AppSettings::default();
@@ -280,4 +852,164 @@ mod tests {
         );
         assert_eq!(mock.get(&MnemonicsType::CustomQuery, "third_query"), None);
     }
+
+    #[test]
+    fn test_config_urgency() {
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let data = String::from(
+            "[urgency]\ndue = 10.5\nage_max = 30\n\n[urgency.user_tag]\nwork = 2.0\n\n[urgency.user_project]\nHome = -1.0\n\n[urgency.uda]\n\"priority.H\" = 8.0\nestimate = 0.5\n",
+        );
+        let _ = file1.write_all(data.as_bytes());
+        let _ = file1.flush();
+
+        let appconf = AppSettings::new(file1.path()).unwrap();
+        assert_eq!(appconf.urgency.due, 10.5);
+        assert_eq!(appconf.urgency.age_max, 30.0);
+        assert_eq!(appconf.urgency.blocked, -5.0);
+        assert_eq!(appconf.urgency.user_tag.get("work"), Some(&2.0));
+        assert_eq!(appconf.urgency.user_tag.get("next"), Some(&15.0));
+        assert_eq!(appconf.urgency.user_project.get("Home"), Some(&-1.0));
+        assert_eq!(appconf.urgency.uda.get("priority.H"), Some(&8.0));
+        assert_eq!(appconf.urgency.uda.get("priority.M"), Some(&3.9));
+        assert_eq!(appconf.urgency.uda.get("estimate"), Some(&0.5));
+    }
+
+    #[test]
+    fn test_config_urgency_validation() {
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let data = String::from("[urgency]\nage_max = -1\n\n[urgency.uda]\n\"priority.\" = 1.0\n");
+        let _ = file1.write_all(data.as_bytes());
+        let _ = file1.flush();
+
+        assert!(AppSettings::new(file1.path()).is_err());
+
+        let mut urgency = UrgencyCoefficients {
+            due: f64::NAN,
+            ..UrgencyCoefficients::default()
+        };
+        urgency.user_tag.insert(String::new(), 1.0);
+        let errors = urgency.validate();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].field, "urgency.due");
+        assert_eq!(errors[1].field, "urgency.user_tag");
+    }
+
+    #[test]
+    fn test_config_hooks() {
+        assert_eq!(AppSettings::default().hooks.timeout, 10);
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let _ = file1.write_all(b"[hooks]\ntimeout = 3\n");
+        let _ = file1.flush();
+        let appconf = AppSettings::new(file1.path()).unwrap();
+        assert_eq!(appconf.hooks.timeout(), std::time::Duration::from_secs(3));
+
+        let _ = file1.rewind();
+        let _ = file1.write_all(b"[hooks]\ntimeout = 0\n");
+        let _ = file1.flush();
+        assert!(AppSettings::new(file1.path()).is_err());
+    }
+
+    #[test]
+    fn test_config_hooks_path() {
+        let taskdb = PathBuf::from("/srv/tasks");
+        let taskrc = Taskrc::from("hooks.location=/etc/task/hooks\n");
+        let settings = HookSettings::default();
+        assert_eq!(
+            settings.hooks_path(None, &taskdb),
+            Some(PathBuf::from("/srv/tasks/hooks"))
+        );
+        assert_eq!(
+            settings.hooks_path(Some(&taskrc), &taskdb),
+            Some(PathBuf::from("/etc/task/hooks"))
+        );
+        let settings = HookSettings {
+            location: Some(PathBuf::from("/opt/hooks")),
+            ..HookSettings::default()
+        };
+        assert_eq!(
+            settings.hooks_path(Some(&taskrc), &taskdb),
+            Some(PathBuf::from("/opt/hooks"))
+        );
+
+        let taskrc = Taskrc::from("hooks=off\nhooks.location=/etc/task/hooks\n");
+        assert_eq!(settings.hooks_path(Some(&taskrc), &taskdb), None);
+        let settings = HookSettings {
+            enabled: Some(true),
+            ..HookSettings::default()
+        };
+        assert_eq!(
+            settings.hooks_path(Some(&taskrc), &taskdb),
+            Some(PathBuf::from("/etc/task/hooks"))
+        );
+    }
+
+    #[test]
+    fn test_config_webhooks() {
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let data = String::from(
+            "[[webhooks]]\nurl = \"https://chat.example.com/hook\"\nevents = [\"add\", \"complete\"]\nfilter = \"+work\"\nsecret = \"shared\"\n\n[[webhooks]]\nurl = \"http://localhost:8080\"\n",
+        );
+        let _ = file1.write_all(data.as_bytes());
+        let _ = file1.flush();
+
+        let appconf = AppSettings::new(file1.path()).unwrap();
+        assert_eq!(appconf.webhooks.len(), 2);
+        let chat = &appconf.webhooks[0];
+        assert!(chat.fires_on(WebhookEvent::Complete));
+        assert!(!chat.fires_on(WebhookEvent::Delete));
+        assert_eq!(chat.secret.as_deref(), Some("shared"));
+        assert!(appconf.webhooks[1].fires_on(WebhookEvent::Delete));
+        assert!(AppSettings::default().webhooks.is_empty());
+
+        let invalid = WebhookSettings {
+            url: String::from("chat.example.com"),
+            events: vec![],
+            filter: Some(String::from("due.before:")),
+            secret: Some(String::new()),
+        };
+        let errors = invalid.validate();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].field, "webhooks.url");
+    }
+
+    #[test]
+    fn test_config_auth() {
+        let mut file1 =
+            NamedTempFile::with_suffix(".toml").expect("Cannot create named temp files.");
+        let data = String::from(
+            "[auth]\nsession_secret = \"kept across restarts\"\n\n[[auth.users]]\nusername = \"alex\"\npassword_hash = \"$argon2id$v=19$m=19456,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$QkfLo1ib6fs3dQ6tkgUNCBUGGdz6ve1rnm4gVWlX4LU\"\n",
+        );
+        let _ = file1.write_all(data.as_bytes());
+        let _ = file1.flush();
+
+        let appconf = AppSettings::new(file1.path()).unwrap();
+        assert!(appconf.auth.enabled());
+        assert!(appconf.auth.user("alex").is_some());
+        assert_eq!(appconf.auth.session_hours, 24 * 7);
+        assert!(!AppSettings::default().auth.enabled());
+
+        let invalid = AuthSettings {
+            users: vec![
+                AuthUser {
+                    username: String::from("alex smith"),
+                    password_hash: String::from("secret"),
+                },
+                AuthUser {
+                    username: String::new(),
+                    password_hash: appconf.auth.users[0].password_hash.clone(),
+                },
+            ],
+            session_secret: Some(String::new()),
+            session_hours: 0,
+            secure_cookie: false,
+        };
+        let errors = invalid.validate();
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors[0].field, "auth.users.username");
+        assert_eq!(errors[1].field, "auth.users.password_hash");
+    }
 }
@@ -0,0 +1,211 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Date expressions as understood by taskwarrior, like `tomorrow`, `eow`, `15th` or `+3d`.
+//!
+//! All dates are evaluated in UTC.
+
+use anyhow::bail;
+use chrono::{
+    DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday,
+};
+
+/// Parses a date expression relative to `now`.
+///
+/// Returns the date and whether it names a whole day instead of a point in time,
+/// like `2025-05-01`, `today` or `monday`.
+pub fn parse_date_expr(
+    value: &str,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, bool), anyhow::Error> {
+    let value = value.trim();
+    if let Some(date) = parse_absolute(value) {
+        return Ok(date);
+    }
+    if let Some(date) = parse_synonym(&value.to_lowercase(), now) {
+        return Ok(date);
+    }
+    if let Some(duration) = parse_duration(value) {
+        let Some(date) = now.checked_add_signed(duration) else {
+            bail!("Date expression {value} is out of range")
+        };
+        return Ok((date, false));
+    }
+    bail!("Unknown date expression {value}")
+}
+
+fn parse_absolute(value: &str) -> Option<(DateTime<Utc>, bool)> {
+    if let Ok(date) = value.parse::<DateTime<Utc>>() {
+        return Some((date, false));
+    }
+    if let Ok(date) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Some((date.and_utc(), false));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|p| (start_of_day(p), true))
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn end_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(23, 59, 59)
+        .expect("23:59:59 is a valid time")
+        .and_utc()
+}
+
+fn first_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// Last day of the period starting at `start` and lasting `months`.
+fn last_day(start: NaiveDate, months: u32) -> Option<NaiveDate> {
+    start
+        .checked_add_months(Months::new(months))?
+        .checked_sub_days(Days::new(1))
+}
+
+fn parse_synonym(value: &str, now: DateTime<Utc>) -> Option<(DateTime<Utc>, bool)> {
+    let today = now.date_naive();
+    let week_start =
+        today.checked_sub_days(Days::new(u64::from(today.weekday().num_days_from_sunday())))?;
+    let month_start = first_of_month(today.year(), today.month())?;
+    let quarter_start = first_of_month(today.year(), (today.month0() / 3) * 3 + 1)?;
+    let year_start = first_of_month(today.year(), 1)?;
+    let date = match value {
+        "now" => (now, false),
+        "today" => (start_of_day(today), true),
+        "sod" => (start_of_day(today), false),
+        "eod" => (end_of_day(today), false),
+        "yesterday" => (start_of_day(today.pred_opt()?), true),
+        "tomorrow" => (start_of_day(today.succ_opt()?), true),
+        "sow" => (start_of_day(week_start), false),
+        "eow" => (
+            end_of_day(week_start.checked_add_days(Days::new(6))?),
+            false,
+        ),
+        "som" => (start_of_day(month_start), false),
+        "eom" => (end_of_day(last_day(month_start, 1)?), false),
+        "soq" => (start_of_day(quarter_start), false),
+        "eoq" => (end_of_day(last_day(quarter_start, 3)?), false),
+        "soy" => (start_of_day(year_start), false),
+        "eoy" => (end_of_day(last_day(year_start, 12)?), false),
+        _ => {
+            let date = parse_weekday(value, today).or_else(|| parse_ordinal(value, today))?;
+            (start_of_day(date), true)
+        }
+    };
+    Some(date)
+}
+
+/// Weekday names like `monday` or `fri` refer to the next such day, never today.
+fn parse_weekday(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let weekday = value.parse::<Weekday>().ok()?;
+    let days_ahead =
+        (7 + weekday.num_days_from_sunday() - today.weekday().num_days_from_sunday()) % 7;
+    let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+    today.checked_add_days(Days::new(u64::from(days_ahead)))
+}
+
+/// Ordinals like `1st` or `15th` refer to the next such day of a month, never today.
+fn parse_ordinal(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let day = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|p| value.strip_suffix(p))?
+        .parse::<u32>()
+        .ok()
+        .filter(|p| (1..=31).contains(p))?;
+    let month_start = first_of_month(today.year(), today.month())?;
+    (0..=12).find_map(|offset| {
+        let date = month_start
+            .checked_add_months(Months::new(offset))?
+            .with_day(day)?;
+        (date > today).then_some(date)
+    })
+}
+
+/// Seconds of the duration units, with months, quarters and years
+/// having a fixed length like in taskwarrior.
+fn unit_seconds(unit: &str) -> Option<i64> {
+    const DAY: i64 = 86_400;
+    let seconds = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600,
+        "d" | "day" | "days" => DAY,
+        "w" | "wk" | "wks" | "week" | "weeks" => 7 * DAY,
+        "mo" | "mos" | "mth" | "mths" | "month" | "months" => 30 * DAY,
+        "q" | "qtr" | "qtrs" | "quarter" | "quarters" => 91 * DAY,
+        "y" | "yr" | "yrs" | "year" | "years" => 365 * DAY,
+        _ => return None,
+    };
+    Some(seconds)
+}
+
+/// Parses durations like `3d`, `+2weeks`, `-1h` or ISO-8601 durations like `P1DT12H`.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let seconds = if let Some(iso) = value.strip_prefix(['P', 'p']) {
+        parse_iso_duration(iso)?
+    } else {
+        let split = value.find(|c: char| !c.is_ascii_digit())?;
+        let (amount, unit) = value.split_at(split);
+        amount
+            .parse::<i64>()
+            .ok()?
+            .checked_mul(unit_seconds(&unit.to_lowercase())?)?
+    };
+    Duration::try_seconds(sign * seconds)
+}
+
+/// Parses the part after `P` of ISO-8601 durations, e.g. `1Y2M3W4DT5H6M7S`.
+fn parse_iso_duration(value: &str) -> Option<i64> {
+    const DAY: i64 = 86_400;
+    let mut seconds = 0;
+    let mut amount = String::new();
+    let mut in_time = false;
+    let mut has_amount = false;
+    for c in value.to_uppercase().chars() {
+        if c.is_ascii_digit() {
+            amount.push(c);
+            continue;
+        }
+        if c == 'T' && amount.is_empty() && !in_time {
+            in_time = true;
+            continue;
+        }
+        let unit = match (in_time, c) {
+            (false, 'Y') => 365 * DAY,
+            (false, 'M') => 30 * DAY,
+            (false, 'W') => 7 * DAY,
+            (false, 'D') => DAY,
+            (true, 'H') => 3_600,
+            (true, 'M') => 60,
+            (true, 'S') => 1,
+            _ => return None,
+        };
+        seconds = amount
+            .parse::<i64>()
+            .ok()?
+            .checked_mul(unit)
+            .and_then(|p| p.checked_add(seconds))?;
+        amount.clear();
+        has_amount = true;
+    }
+    (amount.is_empty() && has_amount).then_some(seconds)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,96 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use super::*;
+use chrono::TimeZone;
+
+/// Thursday, 2025-05-01 08:00
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 5, 1, 8, 0, 0).unwrap()
+}
+
+fn date(value: &str) -> (DateTime<Utc>, bool) {
+    parse_date_expr(value, now()).unwrap()
+}
+
+fn ymd_hms(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+}
+
+#[test]
+fn absolute_dates() {
+    assert_eq!(date("2025-06-01"), (ymd_hms(2025, 6, 1, 0, 0, 0), true));
+    assert_eq!(
+        date("2025-06-01T10:30:00Z"),
+        (ymd_hms(2025, 6, 1, 10, 30, 0), false)
+    );
+    assert_eq!(
+        date("20250601T103000Z"),
+        (ymd_hms(2025, 6, 1, 10, 30, 0), false)
+    );
+}
+
+#[test]
+fn named_dates() {
+    assert_eq!(date("now"), (now(), false));
+    assert_eq!(date("today"), (ymd_hms(2025, 5, 1, 0, 0, 0), true));
+    assert_eq!(date("sod"), (ymd_hms(2025, 5, 1, 0, 0, 0), false));
+    assert_eq!(date("eod"), (ymd_hms(2025, 5, 1, 23, 59, 59), false));
+    assert_eq!(date("yesterday"), (ymd_hms(2025, 4, 30, 0, 0, 0), true));
+    assert_eq!(date("Tomorrow"), (ymd_hms(2025, 5, 2, 0, 0, 0), true));
+    assert_eq!(date("sow"), (ymd_hms(2025, 4, 27, 0, 0, 0), false));
+    assert_eq!(date("eow"), (ymd_hms(2025, 5, 3, 23, 59, 59), false));
+    assert_eq!(date("som"), (ymd_hms(2025, 5, 1, 0, 0, 0), false));
+    assert_eq!(date("eom"), (ymd_hms(2025, 5, 31, 23, 59, 59), false));
+    assert_eq!(date("soq"), (ymd_hms(2025, 4, 1, 0, 0, 0), false));
+    assert_eq!(date("eoq"), (ymd_hms(2025, 6, 30, 23, 59, 59), false));
+    assert_eq!(date("soy"), (ymd_hms(2025, 1, 1, 0, 0, 0), false));
+    assert_eq!(date("eoy"), (ymd_hms(2025, 12, 31, 23, 59, 59), false));
+}
+
+#[test]
+fn weekdays_and_ordinals() {
+    assert_eq!(date("monday"), (ymd_hms(2025, 5, 5, 0, 0, 0), true));
+    assert_eq!(date("fri"), (ymd_hms(2025, 5, 2, 0, 0, 0), true));
+    assert_eq!(date("thursday"), (ymd_hms(2025, 5, 8, 0, 0, 0), true));
+    assert_eq!(date("15th"), (ymd_hms(2025, 5, 15, 0, 0, 0), true));
+    assert_eq!(date("1st"), (ymd_hms(2025, 6, 1, 0, 0, 0), true));
+    assert_eq!(date("31st"), (ymd_hms(2025, 5, 31, 0, 0, 0), true));
+    assert!(parse_date_expr("32nd", now()).is_err());
+}
+
+#[test]
+fn relative_durations() {
+    assert_eq!(date("+3d"), (ymd_hms(2025, 5, 4, 8, 0, 0), false));
+    assert_eq!(date("2h"), (ymd_hms(2025, 5, 1, 10, 0, 0), false));
+    assert_eq!(date("-1w"), (ymd_hms(2025, 4, 24, 8, 0, 0), false));
+    assert_eq!(date("2weeks"), (ymd_hms(2025, 5, 15, 8, 0, 0), false));
+    assert_eq!(date("P1DT12H"), (ymd_hms(2025, 5, 2, 20, 0, 0), false));
+    assert_eq!(date("PT30M"), (ymd_hms(2025, 5, 1, 8, 30, 0), false));
+    assert_eq!(date("P1M"), (ymd_hms(2025, 5, 31, 8, 0, 0), false));
+    for invalid in ["someday", "P", "PT", "P1H", "3x", "d", ""] {
+        assert!(parse_date_expr(invalid, now()).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn huge_durations() {
+    for huge in [
+        "999999999999d",
+        "99999999999d",
+        "-99999999999d",
+        "9223372036854775807s",
+        "99999999999999999999y",
+        "P99999999999D",
+        "P9999999999999999YT9999999999999999H",
+    ] {
+        assert!(parse_date_expr(huge, now()).is_err(), "{huge}");
+    }
+    assert!(parse_duration("9223372036854775807s").is_none());
+}
@@ -11,16 +11,36 @@
 use std::{collections::HashMap, fmt::Display};
 
 use axum::{
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Seconds a client should wait before retrying a request which hit a busy database.
+const RETRY_AFTER_SECONDS: &str = "1";
 
 pub struct AppError(anyhow::Error);
 
+impl AppError {
+    /// Whether the same request is likely to succeed later,
+    /// because the task database was locked by another process.
+    pub fn is_retryable(&self) -> bool {
+        is_database_busy(&self.0)
+    }
+}
+
 // Tell axum how to convert `AppError` into a response.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if self.is_retryable() {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, RETRY_AFTER_SECONDS)],
+                "The task database is busy, please try again.".to_string(),
+            )
+                .into_response();
+        }
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Something went wrong: {}", self.0),
@@ -29,6 +49,27 @@ impl IntoResponse for AppError {
     }
 }
 
+/// Checks whether the error was caused by SQLite giving up on a locked database,
+/// e.g. while the Taskwarrior CLI holds a write transaction.
+pub fn is_database_busy(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(taskchampion::Error::Other(inner)) = cause.downcast_ref::<taskchampion::Error>()
+        {
+            return is_database_busy(inner);
+        }
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error {
+                    code: rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked,
+                    ..
+                },
+                _
+            ))
+        )
+    })
+}
+
 // This enables using `?` on functions that return `Result<_, anyhow::Error>` to turn them into
 // `Result<_, AppError>`. That way you don't need to do that manually.
 impl<E> From<E> for AppError
@@ -46,17 +87,21 @@ impl Display for AppError {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct FieldError {
     pub field: String,
     pub message: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Result of a validation, the errors are grouped by the field they belong to.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct FormValidation {
     pub fields: HashMap<String, Vec<FieldError>>,
     pub msg: Option<String>,
     success: bool,
+    /// The error was caused by a busy task database, see [`is_database_busy`].
+    #[serde(skip)]
+    busy: bool,
 }
 
 impl Default for FormValidation {
@@ -65,6 +110,7 @@ impl Default for FormValidation {
             fields: HashMap::default(),
             msg: None,
             success: true,
+            busy: false,
         }
     }
 }
@@ -107,17 +153,17 @@ impl std::error::Error for FormValidation {
 
 impl From<anyhow::Error> for FormValidation {
     fn from(value: anyhow::Error) -> Self {
-        Self::default()
+        let mut validation = Self::default()
             .set_error(Some(&value.to_string()))
-            .to_owned()
+            .to_owned();
+        validation.busy = is_database_busy(&value);
+        validation
     }
 }
 
 impl From<taskchampion::Error> for FormValidation {
     fn from(value: taskchampion::Error) -> Self {
-        Self::default()
-            .set_error(Some(&value.to_string()))
-            .to_owned()
+        anyhow::Error::from(value).into()
     }
 }
 
@@ -127,6 +173,7 @@ impl FormValidation {
             fields: HashMap::new(),
             msg: Some(msg.to_string()),
             success: false,
+            busy: false,
         }
     }
     pub fn push(&mut self, error: FieldError) {
@@ -144,6 +191,12 @@ impl FormValidation {
         self.success
     }
 
+    /// Whether the same request is likely to succeed later,
+    /// because the task database was locked by another process.
+    pub fn is_retryable(&self) -> bool {
+        self.busy
+    }
+
     pub fn set_error(&mut self, msg: Option<&str>) -> &Self {
         if let Some(err_msg) = msg {
             self.success = false;
@@ -163,3 +216,6 @@ impl FormValidation {
         self.fields.contains_key(field)
     }
 }
+
+#[cfg(test)]
+mod tests;
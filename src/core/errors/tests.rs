@@ -0,0 +1,60 @@
+/*
+ * Copyright 2026 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+
+use super::*;
+
+fn sqlite_error(code: i32) -> anyhow::Error {
+    let err = rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(code), None);
+    taskchampion::Error::from(err).into()
+}
+
+#[test]
+fn test_database_busy_is_retryable() {
+    let busy = sqlite_error(rusqlite::ffi::SQLITE_BUSY);
+    assert!(is_database_busy(&busy));
+    assert!(is_database_busy(&busy.context("Reading tasks")));
+    assert!(is_database_busy(&sqlite_error(
+        rusqlite::ffi::SQLITE_LOCKED
+    )));
+    assert!(!is_database_busy(&sqlite_error(
+        rusqlite::ffi::SQLITE_CORRUPT
+    )));
+    assert!(!is_database_busy(&anyhow::anyhow!("database is locked")));
+
+    let response = AppError::from(sqlite_error(rusqlite::ffi::SQLITE_BUSY)).into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.headers().get(header::RETRY_AFTER).unwrap(),
+        RETRY_AFTER_SECONDS
+    );
+
+    let error = AppError::from(anyhow::anyhow!("Matching task not found"));
+    assert!(!error.is_retryable());
+    assert_eq!(
+        error.into_response().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[test]
+fn test_form_validation_of_busy_database_is_retryable() {
+    let validation = FormValidation::from(sqlite_error(rusqlite::ffi::SQLITE_BUSY));
+    assert!(!validation.is_success());
+    assert!(validation.is_retryable());
+    // the flag is not part of the response.
+    assert!(!serde_json::to_string(&validation).unwrap().contains("busy"));
+
+    let validation = FormValidation::from(anyhow::anyhow!("Matching task not found"));
+    assert!(!validation.is_retryable());
+    assert!(!FormValidation::with_error("Empty description").is_retryable());
+}
@@ -11,5 +11,7 @@
 pub mod app;
 pub mod cache;
 pub mod config;
+pub mod date_expr;
 pub mod errors;
+pub mod taskrc;
 pub mod utils;
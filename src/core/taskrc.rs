@@ -0,0 +1,125 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Settings of the Taskwarrior CLI, read from its taskrc.
+//!
+//! Only `key=value` lines and `include` directives are understood,
+//! which is all the settings used here need.
+
+use std::collections::HashMap;
+use std::env::{self, home_dir};
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, warn};
+
+/// Nesting of `include` directives followed at most.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+pub struct Taskrc {
+    values: HashMap<String, String>,
+}
+
+impl Taskrc {
+    /// Reads the taskrc the Taskwarrior CLI uses, given by `TASKRC`
+    /// or `~/.taskrc`. Returns `None` if there is none.
+    pub fn load() -> Option<Self> {
+        let path = env::var("TASKRC").map_or_else(
+            |_| home_dir().unwrap_or_default().join(".taskrc"),
+            |p| expand_home(&p),
+        );
+        if !path.is_file() {
+            debug!("No taskrc found at {:?}", path);
+            return None;
+        }
+        Some(Self::from_file(&path))
+    }
+
+    /// Reads the taskrc in `path`, unreadable files are skipped.
+    pub fn from_file(path: &Path) -> Self {
+        let mut taskrc = Self::default();
+        taskrc.read_file(path, 0);
+        taskrc
+    }
+
+    fn read_file(&mut self, path: &Path, depth: usize) {
+        match std::fs::read_to_string(path) {
+            Ok(content) => self.parse(&content, path.parent(), depth),
+            Err(e) => warn!("Cannot read taskrc {:?}: {}", path, e),
+        }
+    }
+
+    fn parse(&mut self, content: &str, dir: Option<&Path>, depth: usize) {
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if let Some(include) = line.strip_prefix("include ") {
+                if depth >= MAX_INCLUDE_DEPTH {
+                    warn!("Skipping taskrc include {}, nested too deep", include);
+                    continue;
+                }
+                let mut path = expand_home(include.trim());
+                if let Some(dir) = dir
+                    && path.is_relative()
+                {
+                    path = dir.join(path);
+                }
+                // themes shipped with Taskwarrior are referenced by name only.
+                if path.is_file() {
+                    self.read_file(&path, depth + 1);
+                }
+            } else if let Some((key, value)) = line.split_once('=') {
+                self.values
+                    .insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Reads a flag, Taskwarrior accepts `on`, `yes`, `true` and `1` and their opposites.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)?.to_lowercase().as_str() {
+            "on" | "yes" | "y" | "true" | "1" => Some(true),
+            "off" | "no" | "n" | "false" | "0" => Some(false),
+            value => {
+                warn!("Ignoring taskrc {}={}, it is not a flag", key, value);
+                None
+            }
+        }
+    }
+
+    /// Reads a path, a leading `~` is the home folder.
+    pub fn get_path(&self, key: &str) -> Option<PathBuf> {
+        self.get(key).filter(|p| !p.is_empty()).map(expand_home)
+    }
+}
+
+impl From<&str> for Taskrc {
+    fn from(content: &str) -> Self {
+        let mut taskrc = Self::default();
+        taskrc.parse(content, None, 0);
+        taskrc
+    }
+}
+
+/// Replaces a leading `~` by the home folder.
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,57 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::fs;
+
+use super::*;
+
+#[test]
+fn test_taskrc_values() {
+    let taskrc = Taskrc::from(
+        "# Taskwarrior settings\ndata.location=~/.task\nhooks = off # no scripts\nweekstart=monday\nbroken line\n",
+    );
+    assert_eq!(taskrc.get("weekstart"), Some("monday"));
+    assert_eq!(taskrc.get_bool("hooks"), Some(false));
+    assert_eq!(taskrc.get_bool("weekstart"), None);
+    assert_eq!(taskrc.get("broken line"), None);
+    assert_eq!(
+        taskrc.get_path("data.location"),
+        Some(home_dir().unwrap_or_default().join(".task"))
+    );
+    assert_eq!(Taskrc::from("hooks=1").get_bool("hooks"), Some(true));
+}
+
+#[test]
+fn test_taskrc_include() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    fs::write(
+        tmp_dir.path().join("hooks.rc"),
+        "hooks.location=/etc/task/hooks\n",
+    )?;
+    fs::write(
+        tmp_dir.path().join("taskrc"),
+        "hooks=on\ninclude hooks.rc\ninclude dark-256.theme\n",
+    )?;
+    let taskrc = Taskrc::from_file(&tmp_dir.path().join("taskrc"));
+    assert_eq!(taskrc.get_bool("hooks"), Some(true));
+    assert_eq!(
+        taskrc.get_path("hooks.location"),
+        Some(PathBuf::from("/etc/task/hooks"))
+    );
+
+    // includes of itself end eventually.
+    fs::write(
+        tmp_dir.path().join("loop.rc"),
+        "include loop.rc\nhooks=off\n",
+    )?;
+    let taskrc = Taskrc::from_file(&tmp_dir.path().join("loop.rc"));
+    assert_eq!(taskrc.get_bool("hooks"), Some(false));
+    Ok(())
+}
@@ -0,0 +1,578 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! JSON API for scripts, widgets and other clients, served below `/api/v1`.
+//!
+//! Tasks are sent and returned as [`Task`], errors as [`FormValidation`].
+//! The routes are described by the OpenAPI document at [`OPENAPI_PATH`],
+//! generated from the `utoipa` annotations of the handlers.
+
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, Path, Query, Request, State};
+use axum::http::request::Parts;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use taskchampion::Uuid;
+use tracing::error;
+use utoipa::openapi::content::ContentBuilder;
+use utoipa::openapi::response::ResponseBuilder;
+use utoipa::openapi::schema::{AnyOfBuilder, ObjectBuilder, Schema, Type};
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::{Ref, RefOr};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::backend::auth::SESSION_COOKIE;
+use crate::backend::hooks::HookRejection;
+use crate::backend::task::{Task, UndoGroup, get_task, get_undo_history};
+use crate::core::app::AppState;
+use crate::core::errors::{FieldError, FormValidation, is_database_busy};
+use crate::endpoints::tasks::task_query_builder::{TaskQuery, TaskQueryParams};
+use crate::endpoints::tasks::{
+    change_task_status, list_tasks, patch_task, run_annotate_command, run_modify_command, task_add,
+    task_undo, toggle_task_active,
+};
+use crate::{NewTask, TaskUpdateStatus};
+
+/// Prefix of the routes of the current API version.
+pub const API_PREFIX: &str = "/api/v1";
+/// Path of the OpenAPI document describing the API.
+pub const OPENAPI_PATH: &str = "/api/openapi.json";
+
+#[derive(OpenApi)]
+#[openapi(info(
+    title = "taskwarrior-web",
+    description = "JSON API for the tasks of taskwarrior-web."
+))]
+struct ApiDoc;
+
+/// Routes of the API below [`API_PREFIX`], with their OpenAPI description.
+fn api_router() -> OpenApiRouter<AppState> {
+    let routes = OpenApiRouter::new()
+        .routes(routes!(api_list_tasks, api_create_task))
+        .routes(routes!(api_get_task, api_modify_task))
+        .routes(routes!(api_change_status))
+        .routes(routes!(api_start_task))
+        .routes(routes!(api_stop_task))
+        .routes(routes!(api_annotate_task))
+        .routes(routes!(api_undo_history, api_undo));
+    let mut router = OpenApiRouter::with_openapi(ApiDoc::openapi()).nest(API_PREFIX, routes);
+    document_security(router.get_openapi_mut());
+    router
+}
+
+/// Documents the API tokens and session cookies checked by [`Principal`](crate::endpoints::auth::Principal),
+/// which are only required once users are configured.
+fn document_security(openapi: &mut utoipa::openapi::OpenApi) {
+    let components = openapi.components.get_or_insert_with(Default::default);
+    components.add_security_scheme(
+        "token",
+        SecurityScheme::Http(
+            HttpBuilder::new()
+                .scheme(HttpAuthScheme::Bearer)
+                .description(Some("Personal API token, read-only or read-write"))
+                .build(),
+        ),
+    );
+    components.add_security_scheme(
+        "session",
+        SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_COOKIE))),
+    );
+    openapi.security = Some(vec![
+        SecurityRequirement::new("token", Vec::<String>::new()),
+        SecurityRequirement::new("session", Vec::<String>::new()),
+        SecurityRequirement::default(),
+    ]);
+
+    let error = |description: &str| {
+        ResponseBuilder::new()
+            .description(description)
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("FormValidation")))
+                    .build(),
+            )
+            .build()
+    };
+    for item in openapi.paths.paths.values_mut() {
+        // read-only tokens can only get.
+        for (operation, writes) in [
+            (&mut item.get, false),
+            (&mut item.post, true),
+            (&mut item.patch, true),
+            (&mut item.put, true),
+            (&mut item.delete, true),
+        ] {
+            let Some(operation) = operation else {
+                continue;
+            };
+            let responses = &mut operation.responses.responses;
+            responses.insert(
+                String::from("401"),
+                error("Neither logged in nor a valid API token").into(),
+            );
+            if writes {
+                responses.insert(
+                    String::from("403"),
+                    error("The API token is read-only").into(),
+                );
+            }
+        }
+    }
+}
+
+/// Routes of the JSON API and its OpenAPI document.
+pub fn routes() -> Router<AppState> {
+    let (router, _) = api_router().split_for_parts();
+    router.route(OPENAPI_PATH, get(api_openapi))
+}
+
+/// OpenAPI document of the JSON API.
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    api_router().into_openapi()
+}
+
+async fn api_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+/// Error of an API request, sent as `FormValidation`.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub validation: FormValidation,
+}
+
+impl ApiError {
+    pub fn not_found(uuid: Uuid) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            validation: FormValidation::with_error(&format!("Task {uuid} does not exist")),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status == StatusCode::SERVICE_UNAVAILABLE {
+            return (
+                self.status,
+                [(header::RETRY_AFTER, "1")],
+                Json(self.validation),
+            )
+                .into_response();
+        }
+        (self.status, Json(self.validation)).into_response()
+    }
+}
+
+impl From<FormValidation> for ApiError {
+    fn from(validation: FormValidation) -> Self {
+        let status = if validation.is_retryable() {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        Self { status, validation }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let status = if is_database_busy(&err) {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else if err.downcast_ref::<HookRejection>().is_some() {
+            StatusCode::UNPROCESSABLE_ENTITY
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        match err.downcast::<FormValidation>() {
+            Ok(validation) => validation.into(),
+            Err(err) => {
+                if status == StatusCode::INTERNAL_SERVER_ERROR {
+                    error!("API request failed: {:?}", err);
+                }
+                Self {
+                    status,
+                    validation: err.into(),
+                }
+            }
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self {
+            status: rejection.status(),
+            validation: FormValidation::with_error(&rejection.body_text()),
+        }
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        Self {
+            status: rejection.status(),
+            validation: FormValidation::with_error(&rejection.body_text()),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self {
+            status: rejection.status(),
+            validation: FormValidation::with_error(&rejection.body_text()),
+        }
+    }
+}
+
+/// Extractor like `Json`, `Path` or `Query`, which reports invalid requests
+/// as `FormValidation` instead of plain text.
+pub struct Api<E>(pub E);
+
+impl<S, E> FromRequest<S> for Api<E>
+where
+    S: Send + Sync,
+    E: FromRequest<S>,
+    ApiError: From<E::Rejection>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(E::from_request(req, state).await?))
+    }
+}
+
+impl<S, E> FromRequestParts<S> for Api<E>
+where
+    S: Send + Sync,
+    E: FromRequestParts<S>,
+    ApiError: From<E::Rejection>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(E::from_request_parts(parts, state).await?))
+    }
+}
+
+/// Reads the task for the response, like it is listed.
+async fn find_task(app_state: &AppState, uuid: Uuid) -> Result<Task, ApiError> {
+    let task = get_task(&app_state.replica, uuid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(uuid))?;
+    Ok(task)
+}
+
+/// Lists the tasks matching the query, sorted like the report.
+#[utoipa::path(
+    get,
+    path = "/tasks",
+    params(TaskQueryParams),
+    responses(
+        (status = 200, description = "Matching tasks", body = Vec<Task>),
+        (status = 400, description = "Invalid parameters", body = FormValidation),
+        (status = 422, description = "Invalid filter", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_list_tasks(
+    Api(Query(params)): Api<Query<TaskQueryParams>>,
+    app_state: State<AppState>,
+) -> Result<Json<Vec<Task>>, ApiError> {
+    let task_query = TaskQuery::from(params);
+    let tasks = list_tasks(&task_query, &app_state).await?;
+    Ok(Json(tasks.into_values().collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{id}",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    responses(
+        (status = 200, description = "The task", body = Task),
+        (status = 400, description = "Invalid uuid", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_get_task(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+) -> Result<Json<Task>, ApiError> {
+    Ok(Json(find_task(&app_state, uuid).await?))
+}
+
+/// Creates a task, validated like the tasks added in the browser.
+#[utoipa::path(
+    post,
+    path = "/tasks",
+    request_body = NewTask,
+    responses(
+        (status = 201, description = "The new task", body = Task),
+        (status = 400, description = "Invalid JSON", body = FormValidation),
+        (status = 422, description = "Invalid task or rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_create_task(
+    app_state: State<AppState>,
+    Api(Json(new_task)): Api<Json<NewTask>>,
+) -> Result<(StatusCode, Json<Task>), ApiError> {
+    let uuid = task_add(&new_task, &app_state).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(find_task(&app_state, uuid).await?),
+    ))
+}
+
+/// Change of a task, either as command like `priority:H +next`
+/// or as JSON merge patch of the task.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TaskModification {
+    Command { command: String },
+    Patch(serde_json::Value),
+}
+
+// a command is a valid patch as well, so the variants are `anyOf` instead of
+// the `oneOf` derived for untagged enums.
+impl utoipa::PartialSchema for TaskModification {
+    fn schema() -> RefOr<Schema> {
+        AnyOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .property("command", ObjectBuilder::new().schema_type(Type::String))
+                    .required("command"),
+            )
+            .item(ObjectBuilder::new().description(Some(
+                "Properties of the task to change, `null` removes a property.",
+            )))
+            .description(Some(
+                "Change of a task, either as command like `priority:H +next` \
+                or as JSON merge patch of the task.",
+            ))
+            .into()
+    }
+}
+
+impl ToSchema for TaskModification {}
+
+#[utoipa::path(
+    patch,
+    path = "/tasks/{id}",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    request_body(content(
+        (TaskModification = "application/json"),
+        (TaskModification = "application/merge-patch+json"),
+    )),
+    responses(
+        (status = 200, description = "The changed task", body = Task),
+        (status = 400, description = "Invalid uuid or JSON", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 422, description = "Invalid change or rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_modify_task(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+    Api(Json(modification)): Api<Json<TaskModification>>,
+) -> Result<Json<Task>, ApiError> {
+    find_task(&app_state, uuid).await?;
+    match modification {
+        TaskModification::Command { command } => {
+            run_modify_command(uuid, &command, &app_state).await?;
+        }
+        TaskModification::Patch(patch) => {
+            if !patch.is_object() {
+                return Err(FormValidation::with_error("A patch must be a JSON object").into());
+            }
+            patch_task(uuid, &patch, &app_state).await?;
+        }
+    }
+    Ok(Json(find_task(&app_state, uuid).await?))
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct StatusChange {
+    /// `pending`, `completed` or `deleted`.
+    pub status: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{id}/status",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    request_body = StatusChange,
+    responses(
+        (status = 200, description = "The changed task", body = Task),
+        (status = 400, description = "Invalid uuid or JSON", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 422, description = "Invalid status or rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_change_status(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+    Api(Json(change)): Api<Json<StatusChange>>,
+) -> Result<Json<Task>, ApiError> {
+    if !["pending", "completed", "deleted"].contains(&change.status.as_str()) {
+        let mut validation = FormValidation::default();
+        validation.push(FieldError {
+            field: String::from("status"),
+            message: format!("Unknown status {}", change.status),
+        });
+        return Err(validation.into());
+    }
+    find_task(&app_state, uuid).await?;
+    change_task_status(
+        TaskUpdateStatus {
+            status: change.status,
+            uuid,
+        },
+        &app_state,
+    )
+    .await?;
+    Ok(Json(find_task(&app_state, uuid).await?))
+}
+
+/// Starts the task, other active tasks are stopped.
+#[utoipa::path(
+    post,
+    path = "/tasks/{id}/start",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    responses(
+        (status = 200, description = "The started task", body = Task),
+        (status = 400, description = "Invalid uuid", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 422, description = "Rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_start_task(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+) -> Result<Json<Task>, ApiError> {
+    find_task(&app_state, uuid).await?;
+    toggle_task_active(uuid, String::from("start"), &app_state).await?;
+    Ok(Json(find_task(&app_state, uuid).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{id}/stop",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    responses(
+        (status = 200, description = "The stopped task", body = Task),
+        (status = 400, description = "Invalid uuid", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 422, description = "Rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_stop_task(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+) -> Result<Json<Task>, ApiError> {
+    find_task(&app_state, uuid).await?;
+    toggle_task_active(uuid, String::from("stop"), &app_state).await?;
+    Ok(Json(find_task(&app_state, uuid).await?))
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct NewAnnotation {
+    pub description: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{id}/annotations",
+    params(("id" = Uuid, Path, description = "Uuid of the task")),
+    request_body = NewAnnotation,
+    responses(
+        (status = 201, description = "The annotated task", body = Task),
+        (status = 400, description = "Invalid uuid or JSON", body = FormValidation),
+        (status = 404, description = "No such task", body = FormValidation),
+        (status = 422, description = "Empty annotation or rejected by a hook", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_annotate_task(
+    Api(Path(uuid)): Api<Path<Uuid>>,
+    app_state: State<AppState>,
+    Api(Json(annotation)): Api<Json<NewAnnotation>>,
+) -> Result<(StatusCode, Json<Task>), ApiError> {
+    find_task(&app_state, uuid).await?;
+    run_annotate_command(uuid, &annotation.description, &app_state).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(find_task(&app_state, uuid).await?),
+    ))
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UndoParams {
+    /// Number of undo points to revert, one by default.
+    pub steps: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UndoResponse {
+    /// Number of undo points which were reverted.
+    pub reverted: usize,
+}
+
+/// Lists the changes which can be reverted, the most recent first.
+#[utoipa::path(
+    get,
+    path = "/undo",
+    responses(
+        (status = 200, description = "Changes grouped by undo point", body = Vec<UndoGroup>),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_undo_history(app_state: State<AppState>) -> Result<Json<Vec<UndoGroup>>, ApiError> {
+    Ok(Json(get_undo_history(&app_state.replica).await?))
+}
+
+/// Reverts the last `steps` changes, one by default.
+#[utoipa::path(
+    post,
+    path = "/undo",
+    params(UndoParams),
+    responses(
+        (status = 200, description = "Number of reverted undo points", body = UndoResponse),
+        (status = 400, description = "Invalid parameters", body = FormValidation),
+        (status = 503, description = "The task database is busy", body = FormValidation),
+    )
+)]
+async fn api_undo(
+    Api(Query(params)): Api<Query<UndoParams>>,
+    app_state: State<AppState>,
+) -> Result<Json<UndoResponse>, ApiError> {
+    let steps = params.steps.unwrap_or(1);
+    let reverted = task_undo(steps, &app_state).await?;
+    Ok(Json(UndoResponse { reverted }))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,512 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::collections::HashSet;
+
+use axum::response::IntoResponse;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use serde_json::{Value, json};
+use taskchampion::{Status, Uuid};
+
+use super::{API_PREFIX, ApiError, OPENAPI_PATH, openapi};
+use crate::backend::task::Task;
+use crate::core::errors::FormValidation;
+use crate::endpoints::tests::serve;
+
+async fn create_task(client: &reqwest::Client, url: &str, task: Value) -> anyhow::Result<Task> {
+    let response = client
+        .post(format!("{url}/tasks"))
+        .json(&task)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    Ok(response.json().await?)
+}
+
+#[tokio::test]
+async fn test_api_create_and_list_tasks() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = format!("{}{API_PREFIX}", serve(app_state).await?);
+    let client = reqwest::Client::new();
+
+    let task = create_task(
+        &client,
+        &url,
+        json!({"description": "Write the report", "tags": "+work", "project": "Office", "additional": "priority:H"}),
+    )
+    .await?;
+    assert_eq!(task.description, "Write the report");
+    assert_eq!(task.priority.as_deref(), Some("H"));
+    assert_eq!(task.id, Some(1));
+    create_task(&client, &url, json!({"description": "Water the plants"})).await?;
+
+    let tasks: Vec<Task> = client
+        .get(format!("{url}/tasks"))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(tasks.len(), 2);
+    // the next report sorts by urgency.
+    assert_eq!(tasks[0].uuid, task.uuid);
+    assert!(tasks[0].urgency.is_some());
+
+    let tasks: Vec<Task> = client
+        .get(format!("{url}/tasks"))
+        .query(&[("project", "Office"), ("tags", "+work")])
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(tasks.len(), 1);
+    let tasks: Vec<Task> = client
+        .get(format!("{url}/tasks"))
+        .query(&[("filter", "plants or report"), ("priority", "H")])
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(tasks.len(), 1);
+
+    let found: Task = client
+        .get(format!("{url}/tasks/{}", task.uuid))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(found.description, "Write the report");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_errors() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = format!("{}{API_PREFIX}", serve(app_state).await?);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/tasks"))
+        .json(&json!({"description": " "}))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.has_error("description"));
+
+    let response = client
+        .get(format!("{url}/tasks/{}", Uuid::new_v4()))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let validation: FormValidation = response.json().await?;
+    assert!(!validation.is_success());
+
+    let response = client.get(format!("{url}/tasks/no-uuid")).send().await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.msg.is_some());
+
+    let response = client
+        .get(format!("{url}/tasks"))
+        .query(&[("filter", "(unbalanced")])
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.has_error("filter"));
+
+    let response = client
+        .post(format!("{url}/tasks"))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body("{")
+        .send()
+        .await?;
+    assert!(response.status().is_client_error());
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.msg.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_modify_task() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = format!("{}{API_PREFIX}", serve(app_state).await?);
+    let client = reqwest::Client::new();
+    let task = create_task(
+        &client,
+        &url,
+        json!({"description": "Write the report", "additional": "due:2030-01-01"}),
+    )
+    .await?;
+    let task_url = format!("{url}/tasks/{}", task.uuid);
+    assert!(task.due.is_some());
+
+    let task: Task = client
+        .patch(&task_url)
+        .json(&json!({"command": "priority:M +work"}))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(task.priority.as_deref(), Some("M"));
+    assert_eq!(task.tags, Some(vec![String::from("work")]));
+
+    let response = client
+        .patch(&task_url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/merge-patch+json",
+        )
+        .body(
+            json!({"description": "Write the summary", "due": null, "tags": ["work", "next"]})
+                .to_string(),
+        )
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let task: Task = response.json().await?;
+    assert_eq!(task.description, "Write the summary");
+    assert_eq!(task.due, None);
+    assert_eq!(task.priority.as_deref(), Some("M"));
+    let mut tags = task.tags.clone().unwrap_or_default();
+    tags.sort();
+    assert_eq!(tags, vec!["next", "work"]);
+
+    let response = client
+        .patch(&task_url)
+        .json(&json!({"due": "next week"}))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.has_error("due"));
+
+    let response = client
+        .patch(&task_url)
+        .json(&json!({"command": "start:now"}))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation: FormValidation = response.json().await?;
+    assert!(validation.has_error("additional"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_task_actions() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = format!("{}{API_PREFIX}", serve(app_state).await?);
+    let client = reqwest::Client::new();
+    let task = create_task(&client, &url, json!({"description": "Write the report"})).await?;
+    let task_url = format!("{url}/tasks/{}", task.uuid);
+
+    let task: Task = client
+        .post(format!("{task_url}/start"))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert!(task.start.is_some());
+    let task: Task = client
+        .post(format!("{task_url}/stop"))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert!(task.start.is_none());
+
+    let response = client
+        .post(format!("{task_url}/annotations"))
+        .json(&json!({"description": "Asked for the numbers"}))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let task: Task = response.json().await?;
+    assert_eq!(task.annotations.unwrap().len(), 1);
+
+    let response = client
+        .post(format!("{task_url}/status"))
+        .json(&json!({"status": "waiting"}))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let task: Task = client
+        .post(format!("{task_url}/status"))
+        .json(&json!({"status": "completed"}))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(task.status, Some(Status::Completed));
+
+    let undo: Value = client
+        .post(format!("{url}/undo"))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(undo["reverted"], 1);
+    let task: Task = client.get(&task_url).send().await?.json().await?;
+    assert_eq!(task.status, Some(Status::Pending));
+    let undo: Value = client
+        .post(format!("{url}/undo"))
+        .query(&[("steps", "10")])
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(undo["reverted"], 4);
+    let response = client.get(&task_url).send().await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+/// Client checking the requests and responses against the OpenAPI document,
+/// so a handler returning something else than its annotation says fails the test.
+struct SpecClient {
+    client: reqwest::Client,
+    url: String,
+    spec: Value,
+    checked: HashSet<(String, String)>,
+}
+
+impl SpecClient {
+    fn new(url: &str) -> Self {
+        let mut spec = serde_json::to_value(openapi()).unwrap();
+        // undocumented properties are drift as well.
+        for schema in spec["components"]["schemas"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
+        {
+            if schema.get("properties").is_some() {
+                schema["additionalProperties"] = Value::Bool(false);
+            }
+        }
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches(API_PREFIX).to_string(),
+            spec,
+            checked: HashSet::new(),
+        }
+    }
+
+    fn assert_valid(&self, schema: &Value, instance: &Value, context: &str) {
+        let mut schema = schema.clone();
+        schema["components"] = self.spec["components"].clone();
+        let validator = jsonschema::draft202012::new(&schema).unwrap();
+        if let Err(e) = validator.validate(instance) {
+            panic!("{context} does not match the OpenAPI document: {e}\n{instance}");
+        }
+    }
+
+    /// Sends the request to `path`, which is the documented `template` filled in,
+    /// and returns the response body.
+    async fn send(
+        &mut self,
+        method: &str,
+        template: &str,
+        path: &str,
+        body: Option<(&str, Value)>,
+    ) -> (StatusCode, Value) {
+        let operation = self.spec["paths"][template][method].clone();
+        assert!(
+            operation.is_object(),
+            "{method} {template} is not documented"
+        );
+        let mut request = self.client.request(
+            method.to_uppercase().parse().unwrap(),
+            format!("{}{path}", self.url),
+        );
+        if let Some((content_type, body)) = body {
+            let schema = &operation["requestBody"]["content"][content_type]["schema"];
+            assert!(
+                schema.is_object(),
+                "{content_type} body of {method} {template} is not documented"
+            );
+            self.assert_valid(schema, &body, &format!("Body of {method} {template}"));
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body.to_string());
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        let body: Value = response.json().await.unwrap();
+        let documented = &operation["responses"][status.as_str()];
+        assert!(
+            documented.is_object(),
+            "Status {status} of {method} {template} is not documented"
+        );
+        self.assert_valid(
+            &documented["content"]["application/json"]["schema"],
+            &body,
+            &format!("Response {status} of {method} {template}"),
+        );
+        self.checked
+            .insert((method.to_string(), template.to_string()));
+        (status, body)
+    }
+}
+
+#[tokio::test]
+async fn test_api_matches_openapi() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = format!("{}{API_PREFIX}", serve(app_state).await?);
+    let served: Value = reqwest::get(format!(
+        "{}{OPENAPI_PATH}",
+        url.trim_end_matches(API_PREFIX)
+    ))
+    .await?
+    .json()
+    .await?;
+    assert_eq!(served, serde_json::to_value(openapi())?);
+    let mut client = SpecClient::new(&url);
+    // logins and API tokens are documented, read-only tokens only get.
+    let list = &client.spec["paths"]["/api/v1/tasks"];
+    assert!(list["get"]["responses"]["401"].is_object());
+    assert!(list["get"]["responses"]["403"].is_null());
+    assert!(list["post"]["responses"]["403"].is_object());
+    assert_eq!(
+        client.spec["components"]["securitySchemes"]["token"]["scheme"],
+        "bearer"
+    );
+    let tasks = "/api/v1/tasks";
+    let task = "/api/v1/tasks/{id}";
+
+    let (status, body) = client
+        .send(
+            "post",
+            tasks,
+            tasks,
+            Some((
+                "application/json",
+                json!({"description": "Write the report", "tags": "+work", "additional": "due:2030-01-01"}),
+            )),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uuid = body["uuid"].as_str().unwrap().to_string();
+    let (status, _) = client
+        .send(
+            "post",
+            tasks,
+            tasks,
+            Some(("application/json", json!({"description": ""}))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, body) = client.send("get", tasks, tasks, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    let (status, _) = client
+        .send("get", tasks, "/api/v1/tasks?filter=(unbalanced", None)
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let path = format!("{tasks}/{uuid}");
+    let (status, _) = client.send("get", task, &path, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = client
+        .send("get", task, &format!("{tasks}/{}", Uuid::new_v4()), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = client
+        .send("get", task, "/api/v1/tasks/no-uuid", None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = client
+        .send(
+            "patch",
+            task,
+            &path,
+            Some(("application/json", json!({"command": "priority:H"}))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = client
+        .send(
+            "patch",
+            task,
+            &path,
+            Some(("application/merge-patch+json", json!({"due": null}))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for action in ["start", "stop"] {
+        let (status, _) = client
+            .send(
+                "post",
+                &format!("{task}/{action}"),
+                &format!("{path}/{action}"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = client
+        .send(
+            "post",
+            &format!("{task}/annotations"),
+            &format!("{path}/annotations"),
+            Some((
+                "application/json",
+                json!({"description": "Asked for the numbers"}),
+            )),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = client
+        .send(
+            "post",
+            &format!("{task}/status"),
+            &format!("{path}/status"),
+            Some(("application/json", json!({"status": "completed"}))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = client
+        .send("get", "/api/v1/undo", "/api/v1/undo", None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body[0]["operations"].as_array().unwrap().is_empty());
+    let (status, body) = client
+        .send("post", "/api/v1/undo", "/api/v1/undo?steps=2", None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["reverted"], 2);
+
+    // every documented operation is covered above.
+    for (template, operations) in client.spec["paths"].as_object().unwrap() {
+        for method in operations.as_object().unwrap().keys() {
+            assert!(
+                client.checked.contains(&(method.clone(), template.clone())),
+                "{method} {template} is not checked against the OpenAPI document"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_busy_database_while_writing_is_unavailable() {
+    let busy =
+        rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None);
+    // like a failed commit in `task_add` or `run_modify_command`.
+    let validation = FormValidation::from(taskchampion::Error::from(busy));
+    let response = ApiError::from(validation).into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[RETRY_AFTER], "1");
+
+    let response = ApiError::from(FormValidation::with_error("Empty description")).into_response();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
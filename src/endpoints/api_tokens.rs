@@ -0,0 +1,111 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Settings page to create, list and revoke the API tokens.
+
+use axum::Form;
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{delete, get};
+use serde::Deserialize;
+use taskchampion::Uuid;
+
+use crate::TEMPLATES;
+use crate::backend::api_tokens::TokenScope;
+use crate::core::app::{AppState, get_default_context};
+use crate::core::errors::{AppError, FieldError, FormValidation};
+use crate::endpoints::auth::Principal;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/settings/tokens",
+            get(display_api_tokens).post(create_api_token),
+        )
+        .route("/settings/tokens/{id}", delete(revoke_api_token))
+}
+
+#[derive(Deserialize)]
+pub struct NewApiToken {
+    pub name: String,
+    pub scope: TokenScope,
+}
+
+/// Tokens cannot manage tokens, only logged in users can.
+fn forbid_tokens(principal: &Principal) -> Option<Response> {
+    matches!(principal, Principal::Token(_)).then(|| {
+        (
+            StatusCode::FORBIDDEN,
+            "API tokens cannot be managed with an API token",
+        )
+            .into_response()
+    })
+}
+
+async fn render_api_tokens(
+    app_state: &AppState,
+    new_token: Option<&str>,
+    validation: &FormValidation,
+) -> Result<Response, AppError> {
+    let mut ctx = get_default_context(app_state);
+    ctx.insert("api_tokens", &app_state.api_tokens.list().await?);
+    ctx.insert("new_token", &new_token);
+    ctx.insert("validation", validation);
+    Ok(Html(TEMPLATES.render("api_tokens.html", &ctx)?).into_response())
+}
+
+pub async fn display_api_tokens(
+    principal: Principal,
+    State(app_state): State<AppState>,
+) -> Result<Response, AppError> {
+    if let Some(response) = forbid_tokens(&principal) {
+        return Ok(response);
+    }
+    render_api_tokens(&app_state, None, &FormValidation::default()).await
+}
+
+/// Creates the token and shows it once.
+pub async fn create_api_token(
+    principal: Principal,
+    State(app_state): State<AppState>,
+    Form(form): Form<NewApiToken>,
+) -> Result<Response, AppError> {
+    if let Some(response) = forbid_tokens(&principal) {
+        return Ok(response);
+    }
+    let name = form.name.trim();
+    if name.is_empty() {
+        let mut validation = FormValidation::default();
+        validation.push(FieldError {
+            field: String::from("name"),
+            message: String::from("Name the token after the script using it"),
+        });
+        return render_api_tokens(&app_state, None, &validation).await;
+    }
+    let (_, secret) = app_state.api_tokens.create(name, form.scope).await?;
+    render_api_tokens(&app_state, Some(&secret), &FormValidation::default()).await
+}
+
+pub async fn revoke_api_token(
+    principal: Principal,
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    if let Some(response) = forbid_tokens(&principal) {
+        return Ok(response);
+    }
+    app_state.api_tokens.revoke(id).await?;
+    render_api_tokens(&app_state, None, &FormValidation::default()).await
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,146 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use reqwest::header::{AUTHORIZATION, COOKIE, SET_COOKIE, WWW_AUTHENTICATE};
+use serde_json::json;
+
+use crate::backend::api_tokens::TOKEN_PREFIX;
+use crate::backend::auth::hash_password;
+use crate::core::config::{AppSettings, AuthSettings, AuthUser};
+use crate::endpoints::tests::serve;
+
+/// Logs in and returns the session cookie.
+async fn login(client: &reqwest::Client, url: &str) -> anyhow::Result<String> {
+    let response = client
+        .post(format!("{url}/login"))
+        .form(&[("username", "alex"), ("password", "correct horse")])
+        .send()
+        .await?;
+    let set_cookie = response.headers()[SET_COOKIE].to_str()?;
+    Ok(set_cookie.split(';').next().unwrap().to_string())
+}
+
+/// Creates a token on the settings page and returns it.
+async fn create_token(
+    client: &reqwest::Client,
+    url: &str,
+    cookie: &str,
+    scope: &str,
+) -> anyhow::Result<String> {
+    let response = client
+        .post(format!("{url}/settings/tokens"))
+        .header(COOKIE, cookie)
+        .form(&[("name", "backup cron job"), ("scope", scope)])
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = response.text().await?;
+    assert!(page.contains("backup cron job"));
+    let start = page.find(TOKEN_PREFIX).unwrap();
+    let token = page[start..]
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .next()
+        .unwrap();
+    Ok(token.to_string())
+}
+
+#[tokio::test]
+async fn test_api_tokens() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = crate::get_random_appstate();
+    app_state.app_config = Arc::new(AppSettings {
+        auth: AuthSettings {
+            users: vec![AuthUser {
+                username: String::from("alex"),
+                password_hash: hash_password("correct horse")?,
+            }],
+            ..AuthSettings::default()
+        },
+        ..AppSettings::default()
+    });
+    let url = serve(app_state.clone()).await?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let cookie = login(&client, &url).await?;
+
+    let response = client
+        .post(format!("{url}/settings/tokens"))
+        .header(COOKIE, &cookie)
+        .form(&[("name", " "), ("scope", "read-only")])
+        .send()
+        .await?;
+    assert!(!response.text().await?.contains(TOKEN_PREFIX));
+    let read_only = create_token(&client, &url, &cookie, "read-only").await?;
+    let read_write = create_token(&client, &url, &cookie, "read-write").await?;
+
+    let bearer = |token: &str| format!("Bearer {token}");
+    let response = client
+        .get(format!("{url}/api/v1/tasks"))
+        .header(AUTHORIZATION, bearer(&read_only))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .get(format!("{url}/hooks/log"))
+        .header(AUTHORIZATION, bearer(&read_only))
+        .header("HX-Request", "true")
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let new_task = json!({"description": "Water the plants"});
+    let response = client
+        .post(format!("{url}/api/v1/tasks"))
+        .header(AUTHORIZATION, bearer(&read_only))
+        .json(&new_task)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = client
+        .post(format!("{url}/api/v1/tasks"))
+        .header(AUTHORIZATION, bearer(&read_write))
+        .json(&new_task)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = client
+        .get(format!("{url}/api/v1/tasks"))
+        .header(AUTHORIZATION, bearer("twk_revoked"))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+    // tokens cannot create more tokens.
+    let response = client
+        .get(format!("{url}/settings/tokens"))
+        .header(AUTHORIZATION, bearer(&read_write))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let tokens = app_state.api_tokens.list().await?;
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.iter().all(|p| p.last_used.is_some()));
+    let response = client
+        .delete(format!("{url}/settings/tokens/{}", tokens[0].id))
+        .header(COOKIE, &cookie)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .get(format!("{url}/api/v1/tasks"))
+        .header(AUTHORIZATION, bearer(&read_only))
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    Ok(())
+}
@@ -0,0 +1,211 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Login for the users in the `[auth]` settings.
+//!
+//! [`require_login`] guards the other routes, scripts can send
+//! an API token instead of logging in, see [`Principal`].
+
+use axum::Form;
+use axum::Router;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum_extra::extract::SignedCookieJar;
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::backend::api_tokens::ApiToken;
+use crate::backend::auth::{SESSION_COOKIE, Session, verify_password};
+use crate::core::app::{AppState, get_default_context};
+use crate::core::errors::{AppError, FormValidation};
+use crate::endpoints::api::ApiError;
+use crate::{DIST_CONTENT, TEMPLATES};
+
+pub const LOGIN_PATH: &str = "/login";
+pub const LOGOUT_PATH: &str = "/logout";
+
+/// Login and logout, reachable without a session.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(LOGIN_PATH, get(display_login).post(login))
+        .route(LOGOUT_PATH, post(logout))
+}
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    pub username: String,
+    pub password: String,
+}
+
+fn render_login(app_state: &AppState, username: &str, validation: &FormValidation) -> Html<String> {
+    let mut ctx = get_default_context(app_state);
+    ctx.insert("username", username);
+    ctx.insert("validation", validation);
+    ctx.insert(
+        "STYLESHEET_URL",
+        DIST_CONTENT
+            .get_file("style.css")
+            .and_then(|p| p.contents_utf8())
+            .unwrap_or_default(),
+    );
+    Html(TEMPLATES.render("login.html", &ctx).unwrap())
+}
+
+async fn display_login(State(app_state): State<AppState>) -> Response {
+    if !app_state.app_config.auth.enabled() {
+        return Redirect::to("/").into_response();
+    }
+    render_login(&app_state, "", &FormValidation::default()).into_response()
+}
+
+async fn login(
+    State(app_state): State<AppState>,
+    jar: SignedCookieJar,
+    Form(form): Form<LoginForm>,
+) -> Response {
+    let settings = app_state.app_config.clone();
+    let (username, password) = (form.username.clone(), form.password);
+    // argon2 is slow on purpose, keep it off the runtime threads.
+    let valid =
+        tokio::task::spawn_blocking(move || verify_password(&settings.auth, &username, &password))
+            .await
+            .unwrap_or(false);
+    if !valid {
+        info!("Failed login of user {:?}", form.username);
+        let validation = FormValidation::with_error("Invalid username or password");
+        return (
+            StatusCode::UNAUTHORIZED,
+            render_login(&app_state, &form.username, &validation),
+        )
+            .into_response();
+    }
+
+    let auth = &app_state.app_config.auth;
+    let session = Session::new(&form.username, auth);
+    let max_age = (session.expires - chrono::Utc::now()).num_seconds();
+    let cookie = Cookie::build((SESSION_COOKIE, session.cookie_value()))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(auth.secure_cookie)
+        .max_age(time::Duration::seconds(max_age));
+    (jar.add(cookie), Redirect::to("/")).into_response()
+}
+
+async fn logout(jar: SignedCookieJar) -> impl IntoResponse {
+    (
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/")),
+        Redirect::to(LOGIN_PATH),
+    )
+}
+
+/// Sender of a request, checked against the sessions and API tokens.
+///
+/// As extractor, requests without a valid session or token are rejected:
+/// pages are redirected to the login page, htmx requests get an `HX-Redirect`
+/// to it and the API and token requests get a 401 response.
+/// Read-only tokens are rejected with 403 for anything but reading.
+#[derive(Clone, Debug)]
+pub enum Principal {
+    /// No users are configured, everyone is let in.
+    Anonymous,
+    User(Session),
+    Token(ApiToken),
+}
+
+fn reject(parts: &Parts, status: StatusCode, msg: &str) -> Response {
+    let bearer = parts.headers.contains_key(header::AUTHORIZATION);
+    if bearer || parts.uri.path().starts_with("/api/") {
+        let error = ApiError {
+            status,
+            validation: FormValidation::with_error(msg),
+        };
+        let mut response = error.into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    } else if parts.headers.contains_key("HX-Request") {
+        (status, [("HX-Redirect", LOGIN_PATH)]).into_response()
+    } else {
+        Redirect::to(LOGIN_PATH).into_response()
+    }
+}
+
+impl FromRequestParts<AppState> for Principal {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(principal) = parts.extensions.get::<Self>() {
+            return Ok(principal.clone());
+        }
+        let auth = &app_state.app_config.auth;
+        if !auth.enabled() {
+            return Ok(Self::Anonymous);
+        }
+
+        if let Some(authorization) = parts.headers.get(header::AUTHORIZATION) {
+            let Some(secret) = authorization
+                .to_str()
+                .ok()
+                .and_then(|p| p.strip_prefix("Bearer "))
+            else {
+                return Err(reject(
+                    parts,
+                    StatusCode::UNAUTHORIZED,
+                    "Bearer token expected",
+                ));
+            };
+            let token = match app_state.api_tokens.verify(secret.trim()).await {
+                Ok(Some(token)) => token,
+                Ok(None) => {
+                    return Err(reject(parts, StatusCode::UNAUTHORIZED, "Invalid API token"));
+                }
+                Err(e) => {
+                    error!("Cannot read the API tokens: {:?}", e);
+                    return Err(AppError::from(e).into_response());
+                }
+            };
+            if !token.scope.allows(&parts.method) {
+                return Err(reject(
+                    parts,
+                    StatusCode::FORBIDDEN,
+                    "The API token is read-only",
+                ));
+            }
+            return Ok(Self::Token(token));
+        }
+
+        let jar = SignedCookieJar::from_headers(&parts.headers, app_state.session_key.clone());
+        jar.get(SESSION_COOKIE)
+            .and_then(|p| Session::from_cookie_value(p.value(), auth))
+            .map(Self::User)
+            .ok_or_else(|| reject(parts, StatusCode::UNAUTHORIZED, "Login required"))
+    }
+}
+
+/// Lets only requests of a [`Principal`] through and adds it to the extensions.
+pub async fn require_login(principal: Principal, mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests;
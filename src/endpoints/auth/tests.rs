@@ -0,0 +1,146 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use reqwest::header::{COOKIE, LOCATION, SET_COOKIE};
+
+use crate::backend::auth::hash_password;
+use crate::core::app::AppState;
+use crate::core::config::{AppSettings, AuthSettings, AuthUser};
+use crate::core::errors::FormValidation;
+use crate::endpoints::tests::serve;
+
+fn with_user(app_state: &mut AppState) {
+    app_state.app_config = Arc::new(AppSettings {
+        auth: AuthSettings {
+            users: vec![AuthUser {
+                username: String::from("alex"),
+                password_hash: hash_password("correct horse").unwrap(),
+            }],
+            ..AuthSettings::default()
+        },
+        ..AppSettings::default()
+    });
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_no_login_without_users() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let url = serve(app_state).await?;
+    let client = client();
+
+    let response = client.get(format!("{url}/hooks/log")).send().await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client.get(format!("{url}/login")).send().await?;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_login_required() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = crate::get_random_appstate();
+    with_user(&mut app_state);
+    let url = serve(app_state).await?;
+    let client = client();
+
+    let response = client.get(format!("{url}/hooks/log")).send().await?;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/login");
+    let response = client
+        .get(format!("{url}/hooks/log"))
+        .header("HX-Request", "true")
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["HX-Redirect"], "/login");
+    let response = client.get(format!("{url}/api/v1/tasks")).send().await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let error: FormValidation = response.json().await?;
+    assert_eq!(error.msg.as_deref(), Some("Login required"));
+
+    let response = client.get(format!("{url}/login")).send().await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.text().await?.contains("name=\"password\""));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_login_and_logout() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = crate::get_random_appstate();
+    with_user(&mut app_state);
+    let url = serve(app_state).await?;
+    let client = client();
+
+    let response = client
+        .post(format!("{url}/login"))
+        .form(&[("username", "alex"), ("password", "battery staple")])
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(response.headers().get(SET_COOKIE).is_none());
+    assert!(
+        response
+            .text()
+            .await?
+            .contains("Invalid username or password")
+    );
+
+    let response = client
+        .post(format!("{url}/login"))
+        .form(&[("username", "alex"), ("password", "correct horse")])
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/");
+    let set_cookie = response.headers()[SET_COOKIE].to_str()?;
+    assert!(set_cookie.starts_with("twk_session="));
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("SameSite=Lax"));
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+
+    let response = client
+        .get(format!("{url}/api/v1/tasks"))
+        .header(COOKIE, &cookie)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    // the signature covers the user.
+    let forged = cookie.replace("alex", "sam");
+    let response = client
+        .get(format!("{url}/hooks/log"))
+        .header(COOKIE, &forged)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let response = client
+        .post(format!("{url}/logout"))
+        .header(COOKIE, &cookie)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[LOCATION], "/login");
+    assert!(
+        response.headers()[SET_COOKIE]
+            .to_str()?
+            .starts_with("twk_session=;")
+    );
+    Ok(())
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2026 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::time::Duration;
+
+use axum::Router;
+use axum::routing::get;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::*;
+
+/// Reads from the stream until the received text contains `expected`.
+async fn read_until(stream: &mut TcpStream, expected: &str) -> anyhow::Result<String> {
+    let mut received = String::new();
+    let mut buffer = [0; 1024];
+    while !received.contains(expected) {
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buffer)).await??;
+        anyhow::ensure!(read > 0, "Connection closed, received: {received}");
+        received.push_str(&String::from_utf8_lossy(&buffer[..read]));
+    }
+    Ok(received)
+}
+
+#[tokio::test]
+async fn test_task_events() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let task_changes = app_state.task_changes.clone();
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let app = Router::new()
+        .route("/events", get(task_events))
+        .with_state(app_state);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut stream = TcpStream::connect(addr).await?;
+    stream
+        .write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n\r\n")
+        .await?;
+    let headers = read_until(&mut stream, "\r\n\r\n").await?;
+    assert!(headers.starts_with("HTTP/1.1 200"));
+    assert!(headers.contains("text/event-stream"));
+
+    task_changes.notify();
+    let received = read_until(&mut stream, "\n\n").await?;
+    assert!(received.contains(&format!("event: {TASKS_CHANGED_EVENT}")));
+    assert!(received.contains("data: "));
+    Ok(())
+}
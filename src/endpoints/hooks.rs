@@ -0,0 +1,30 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Recent invocations of the Taskwarrior hooks.
+
+use axum::extract::State;
+use axum::response::Html;
+
+use crate::TEMPLATES;
+use crate::core::app::{AppState, get_default_context};
+
+/// Path of the hook log, relative like the other views.
+pub const HOOK_LOG_PATH: &str = "hooks/log";
+
+/// Shows the recent hook invocations, the most recent first.
+pub async fn display_hook_log(app_state: State<AppState>) -> Html<String> {
+    let mut ctx = get_default_context(&app_state);
+    ctx.insert("hook_log", &app_state.hook_log.entries());
+    Html(TEMPLATES.render("hooks_log.html", &ctx).unwrap())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use tera::Context;
+
+use super::*;
+use crate::backend::hooks::track_hook_failures;
+use crate::endpoints::tasks::task_add;
+use crate::{FlashMsg, FlashMsgRoles, NewTask};
+
+#[tokio::test]
+async fn test_display_hook_log() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    fs::create_dir_all(&hooks_dir)?;
+    let hook_file = hooks_dir.join("on-add.notify");
+    fs::write(
+        &hook_file,
+        "#!/bin/bash\necho \"notify-send: <missing>\" >&2\nexit 3\n",
+    )?;
+    fs::set_permissions(&hook_file, fs::Permissions::from_mode(0o755))?;
+
+    let Html(page) = display_hook_log(State(app_state.clone())).await;
+    assert!(page.contains("No hooks were run"));
+
+    let new_task = NewTask::new(Some("Send the invoice".into()), None, None, None, None);
+    let (added, hooks_failed) = track_hook_failures(task_add(&new_task, &app_state)).await;
+    assert!(added.is_err());
+    assert!(hooks_failed);
+
+    let Html(page) = display_hook_log(State(app_state.clone())).await;
+    assert!(page.contains("on-add.notify"));
+    assert!(page.contains("notify-send: &lt;missing&gt;"));
+    assert!(page.contains(">3<"));
+
+    // the flash message of the action links the log.
+    let mut ctx = Context::new();
+    FlashMsg::new("Task updated", None, FlashMsgRoles::Success)
+        .with_hook_failures(hooks_failed)
+        .to_context(&mut ctx);
+    assert_eq!(ctx.get("toast_link"), Some(&HOOK_LOG_PATH.into()));
+    let mut ctx = Context::new();
+    FlashMsg::new("Task updated", None, FlashMsgRoles::Success)
+        .with_hook_failures(false)
+        .to_context(&mut ctx);
+    assert!(ctx.get("toast_link").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hook_failures_of_concurrent_actions() -> anyhow::Result<()> {
+    let (_tmp_dir, app_state) = crate::get_random_appstate();
+    let hooks_dir = app_state.task_hooks_path.clone().unwrap();
+    fs::create_dir_all(&hooks_dir)?;
+    let hook_file = hooks_dir.join("on-add.invoices");
+    fs::write(
+        &hook_file,
+        "#!/bin/bash
+read task
+if [[ $task == *invoice* ]]; then
+  sleep 0.2
+  echo \"No invoices\"
+  exit 1
+fi
+echo \"$task\"
+",
+    )?;
+    fs::set_permissions(&hook_file, fs::Permissions::from_mode(0o755))?;
+
+    let rejected = NewTask::new(Some("Send the invoice".into()), None, None, None, None);
+    let accepted = NewTask::new(Some("Call the bank".into()), None, None, None, None);
+    let ((rejected, rejected_failed), (accepted, accepted_failed)) = tokio::join!(
+        track_hook_failures(task_add(&rejected, &app_state)),
+        track_hook_failures(task_add(&accepted, &app_state)),
+    );
+    assert!(rejected.is_err());
+    assert!(rejected_failed);
+    // the failure of the other action is not reported.
+    assert!(accepted.is_ok());
+    assert!(!accepted_failed);
+    Ok(())
+}
@@ -8,4 +8,76 @@
  * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
+pub mod api;
+pub mod api_tokens;
+pub mod auth;
+pub mod events;
+pub mod hooks;
+pub mod sync_server;
 pub mod tasks;
+pub mod ui;
+
+use axum::Router;
+use axum::middleware;
+use axum::routing::{get, post};
+use tracing::info;
+
+use crate::core::app::AppState;
+use auth::require_login;
+use events::task_events;
+use hooks::display_hook_log;
+use tasks::{
+    api_denotate_task_entry, api_edit_task_annotation, display_task_delete, display_task_details,
+};
+use ui::{
+    check_and_sync, create_new_task, display_flash_message, display_task_add_window,
+    do_task_actions, front_page, get_active_task, get_bar, get_tag_bar, get_task_action_bar,
+    get_undo_report, just_empty, tasks_display, undo_last_change, update_task_details,
+};
+
+/// All routes of taskwarrior-web, as served by `main`.
+pub fn app(app_state: AppState) -> Router {
+    let mut app = Router::new()
+        .route("/", get(front_page))
+        .route("/tasks", get(tasks_display))
+        .route("/tasks", post(do_task_actions))
+        .route("/tasks/undo/report", get(get_undo_report))
+        .route("/tasks/undo/confirmed", post(undo_last_change))
+        .route("/tasks/add", get(display_task_add_window))
+        .route("/tasks/active", get(get_active_task))
+        .route("/tasks/add", post(create_new_task))
+        .route("/tasks/{id}/details", get(display_task_details))
+        .route("/tasks/{id}/details", post(update_task_details))
+        .route("/tasks/{id}/delete", get(display_task_delete))
+        .route("/tasks/{id}/denotate", post(api_denotate_task_entry))
+        .route("/tasks/{id}/annotation", post(api_edit_task_annotation))
+        .route("/msg", get(display_flash_message))
+        .route("/msg_clr", get(just_empty))
+        .route("/tag_bar", get(get_tag_bar))
+        .route("/task_action_bar", get(get_task_action_bar))
+        .route("/bars", get(get_bar))
+        .route("/sync", post(check_and_sync))
+        .route("/events", get(task_events))
+        .route("/hooks/log", get(display_hook_log))
+        .merge(api::routes())
+        .merge(api_tokens::routes())
+        // all routes above need a login or an API token once users are configured.
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            require_login,
+        ))
+        .merge(auth::routes())
+        .nest_service("/dist", tower_http::services::ServeDir::new("./dist"));
+    // sync clients cannot log in, they are checked against `sync_server.client_ids`.
+    if app_state.app_config.sync_server.enabled {
+        info!(
+            "Sync server enabled, storing versions in {:?}",
+            app_state.sync_server.root()
+        );
+        app = app.merge(sync_server::routes());
+    }
+    app.with_state(app_state)
+}
+
+#[cfg(test)]
+pub(crate) mod tests;
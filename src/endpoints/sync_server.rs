@@ -0,0 +1,197 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Routes of the embedded taskchampion sync server.
+//! The protocol is the one spoken by `taskchampion-sync-server`,
+//! so replicas can use the url of taskwarrior-web as `sync.server.url`.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{HeaderMap, Response, StatusCode, header};
+use axum::routing::{get, post};
+use taskchampion::Uuid;
+use taskchampion::server::{SnapshotUrgency, VersionId};
+use tracing::error;
+
+use crate::backend::sync_server::{AddVersionOutcome, ChildVersionOutcome};
+use crate::core::app::AppState;
+
+const HISTORY_SEGMENT_CONTENT_TYPE: &str = "application/vnd.taskchampion.history-segment";
+const SNAPSHOT_CONTENT_TYPE: &str = "application/vnd.taskchampion.snapshot";
+/// Snapshots contain the whole task database.
+const MAX_BODY_SIZE: usize = 100 * 1024 * 1024;
+
+/// Routes speaking the taskchampion sync protocol.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/client/add-version/{parent_version_id}",
+            post(add_version),
+        )
+        .route(
+            "/v1/client/get-child-version/{parent_version_id}",
+            get(get_child_version),
+        )
+        .route("/v1/client/add-snapshot/{version_id}", post(add_snapshot))
+        .route("/v1/client/snapshot", get(get_snapshot))
+        .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+}
+
+fn empty_response(status: StatusCode) -> Response<axum::body::Body> {
+    Response::builder()
+        .status(status)
+        .body(axum::body::Body::empty())
+        .unwrap()
+}
+
+/// Reads the client id and checks whether the client may use the server.
+fn client_id(headers: &HeaderMap, app_state: &AppState) -> Result<Uuid, StatusCode> {
+    let client_id = headers
+        .get("X-Client-Id")
+        .and_then(|p| p.to_str().ok())
+        .and_then(|p| Uuid::parse_str(p).ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    if app_state.app_config.sync_server.allows_client(client_id) {
+        Ok(client_id)
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+fn has_content_type(headers: &HeaderMap, content_type: &str) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|p| p.to_str().ok())
+        == Some(content_type)
+}
+
+fn internal_error(e: &anyhow::Error) -> Response<axum::body::Body> {
+    error!("Sync server failed: {:?}", e);
+    empty_response(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn add_version(
+    Path(parent_version_id): Path<VersionId>,
+    app_state: State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<axum::body::Body> {
+    let client_id = match client_id(&headers, &app_state) {
+        Ok(client_id) => client_id,
+        Err(status) => return empty_response(status),
+    };
+    if !has_content_type(&headers, HISTORY_SEGMENT_CONTENT_TYPE) {
+        return empty_response(StatusCode::BAD_REQUEST);
+    }
+    match app_state
+        .sync_server
+        .add_version(client_id, parent_version_id, &body)
+        .await
+    {
+        Ok(AddVersionOutcome::Added {
+            version_id,
+            snapshot_urgency,
+        }) => {
+            let mut response = Response::builder()
+                .status(StatusCode::OK)
+                .header("X-Version-Id", version_id.to_string());
+            match snapshot_urgency {
+                SnapshotUrgency::None => {}
+                SnapshotUrgency::Low => {
+                    response = response.header("X-Snapshot-Request", "urgency=low");
+                }
+                SnapshotUrgency::High => {
+                    response = response.header("X-Snapshot-Request", "urgency=high");
+                }
+            }
+            response.body(axum::body::Body::empty()).unwrap()
+        }
+        Ok(AddVersionOutcome::ExpectedParentVersion(expected)) => Response::builder()
+            .status(StatusCode::CONFLICT)
+            .header("X-Parent-Version-Id", expected.to_string())
+            .body(axum::body::Body::empty())
+            .unwrap(),
+        Err(e) => internal_error(&e),
+    }
+}
+
+async fn get_child_version(
+    Path(parent_version_id): Path<VersionId>,
+    app_state: State<AppState>,
+    headers: HeaderMap,
+) -> Response<axum::body::Body> {
+    let client_id = match client_id(&headers, &app_state) {
+        Ok(client_id) => client_id,
+        Err(status) => return empty_response(status),
+    };
+    match app_state
+        .sync_server
+        .get_child_version(client_id, parent_version_id)
+        .await
+    {
+        Ok(ChildVersionOutcome::Found(version)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, HISTORY_SEGMENT_CONTENT_TYPE)
+            .header("X-Version-Id", version.version_id.to_string())
+            .header("X-Parent-Version-Id", version.parent_version_id.to_string())
+            .body(axum::body::Body::from(version.history_segment))
+            .unwrap(),
+        Ok(ChildVersionOutcome::UpToDate) => empty_response(StatusCode::NOT_FOUND),
+        Ok(ChildVersionOutcome::Gone) => empty_response(StatusCode::GONE),
+        Err(e) => internal_error(&e),
+    }
+}
+
+async fn add_snapshot(
+    Path(version_id): Path<VersionId>,
+    app_state: State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response<axum::body::Body> {
+    let client_id = match client_id(&headers, &app_state) {
+        Ok(client_id) => client_id,
+        Err(status) => return empty_response(status),
+    };
+    if !has_content_type(&headers, SNAPSHOT_CONTENT_TYPE) {
+        return empty_response(StatusCode::BAD_REQUEST);
+    }
+    match app_state
+        .sync_server
+        .add_snapshot(client_id, version_id, &body)
+        .await
+    {
+        Ok(_) => empty_response(StatusCode::OK),
+        Err(e) => internal_error(&e),
+    }
+}
+
+async fn get_snapshot(
+    app_state: State<AppState>,
+    headers: HeaderMap,
+) -> Response<axum::body::Body> {
+    let client_id = match client_id(&headers, &app_state) {
+        Ok(client_id) => client_id,
+        Err(status) => return empty_response(status),
+    };
+    match app_state.sync_server.get_snapshot(client_id).await {
+        Ok(Some((version_id, snapshot))) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, SNAPSHOT_CONTENT_TYPE)
+            .header("X-Version-Id", version_id.to_string())
+            .body(axum::body::Body::from(snapshot))
+            .unwrap(),
+        Ok(None) => empty_response(StatusCode::NOT_FOUND),
+        Err(e) => internal_error(&e),
+    }
+}
+
+#[cfg(test)]
+mod tests;
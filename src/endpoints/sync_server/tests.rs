@@ -0,0 +1,109 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use taskchampion::{Operations, Status, Uuid};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::backend::replica::ReplicaPool;
+use crate::backend::sync::sync_replica;
+use crate::backend::task::get_replica;
+use crate::core::app::AppState;
+use crate::core::config::{RemoteSyncServer, SyncSettings};
+use crate::endpoints::tests::serve;
+
+/// Serves the [`app`] with the sync server enabled for `client_ids`, returns the url.
+async fn serve_sync_server(mut app_state: AppState, client_ids: &[Uuid]) -> anyhow::Result<String> {
+    let sync_server = &mut std::sync::Arc::make_mut(&mut app_state.app_config).sync_server;
+    sync_server.enabled = true;
+    sync_server.client_ids = client_ids.iter().map(Uuid::to_string).collect();
+    serve(app_state).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_against_embedded_server() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let client_id = Uuid::new_v4();
+    let url = serve_sync_server(app_state.clone(), &[client_id]).await?;
+    let settings = SyncSettings {
+        server: Some(RemoteSyncServer {
+            url,
+            client_id: client_id.to_string(),
+        }),
+        encryption_secret: Some("shared secret".into()),
+        ..SyncSettings::default()
+    };
+    let replica_a = tempfile::tempdir()?;
+    let replica_b = tempfile::tempdir()?;
+
+    let uuid = Uuid::new_v4();
+    let mut replica = get_replica(replica_a.path()).await?;
+    let mut ops = Operations::new();
+    let mut task = replica.create_task(uuid, &mut ops).await?;
+    task.set_description("Served by taskwarrior-web".into(), &mut ops)?;
+    task.set_status(Status::Pending, &mut ops)?;
+    replica.commit_operations(ops).await?;
+
+    let report = sync_replica(&ReplicaPool::new(replica_a.path().into()), &settings).await?;
+    assert_eq!(report.pushed, 1);
+    let report = sync_replica(&ReplicaPool::new(replica_b.path().into()), &settings).await?;
+    assert_eq!(report.pulled, 1);
+    let mut replica = get_replica(replica_b.path()).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_description(), "Served by taskwarrior-web");
+
+    // a replica with a different secret cannot read the versions
+    let replica_c = tempfile::tempdir()?;
+    let wrong_secret = SyncSettings {
+        encryption_secret: Some("another secret".into()),
+        ..settings.clone()
+    };
+    assert!(
+        sync_replica(&ReplicaPool::new(replica_c.path().into()), &wrong_secret)
+            .await
+            .is_err()
+    );
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_server_requires_client_id() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let url = serve_sync_server(app_state, &[Uuid::new_v4()]).await?;
+    let mut stream = tokio::net::TcpStream::connect(url.trim_start_matches("http://")).await?;
+    stream
+        .write_all(
+            b"GET /v1/client/snapshot HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    assert!(response.starts_with("HTTP/1.1 400"));
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_server_denies_unknown_client() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let url = serve_sync_server(app_state, &[]).await?;
+    let mut stream = tokio::net::TcpStream::connect(url.trim_start_matches("http://")).await?;
+    let request = format!(
+        "GET /v1/client/snapshot HTTP/1.1\r\nHost: localhost\r\nX-Client-Id: {}\r\nConnection: close\r\n\r\n",
+        Uuid::new_v4()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    assert!(response.starts_with("HTTP/1.1 403"));
+    let _ = tmp_dir.close();
+    Ok(())
+}
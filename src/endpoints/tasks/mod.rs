@@ -16,79 +16,39 @@ use axum::http::{Response, StatusCode};
 use chrono::Utc;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::process::Command;
 use std::str::FromStr;
 use task_modify::{
     task_apply_depends, task_apply_description, task_apply_priority, task_apply_recur,
     task_apply_status, task_apply_tag_add, task_apply_tag_remove, task_apply_timestamps,
+    task_validate_end,
 };
 use taskchampion::storage::Storage;
 use taskchampion::{Operation, Operations, Replica, Status, Tag, Uuid};
 use tera::Context;
 use tracing::{debug, error, info, trace};
 
+pub mod task_filter;
 pub mod task_query_builder;
 
+use crate::backend::hooks::{TaskEvent, apply_hook_changes, run_exit_hooks, run_hooks_on_task};
 use crate::backend::task::{
-    Annotation, TaskEvent, TaskProperties, convert_task_status, denotate_task, execute_hooks,
-    get_replica, get_task,
+    Annotation, TaskProperties, annotate_task, convert_task_status, denotate_task, edit_annotation,
+    get_task, get_tasks, undo_operations,
 };
+use crate::backend::webhooks::queue_webhooks;
 use crate::core::app::{AppState, get_default_context};
 use crate::core::config::CustomQuery;
-use crate::core::errors::{FieldError, FormValidation};
+use crate::core::date_expr::parse_date_expr;
+use crate::core::errors::{AppError, FieldError, FormValidation, is_database_busy};
 use crate::core::utils::make_shortcut;
 use crate::{NewTask, TEMPLATES, TaskUpdateStatus, DIST_CONTENT};
-use task_query_builder::TaskQuery;
+use task_filter::TaskFilter;
+use task_query_builder::{TaskQuery, TaskReport};
 
 pub(crate) mod task_modify;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
-pub struct Task {
-    pub id: i64,
-    #[serde(skip_serializing_if = "String::is_empty")]
-    pub description: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub end: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub project: Option<String>,
-    pub uuid: String,
-    pub urgency: Option<f64>,
-    pub entry: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub until: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scheduled: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub annotations: Option<Vec<Annotation>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub due: Option<String>,
-    pub modified: Option<String>,
-    pub status: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub wait: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub priority: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub depends: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub recur: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub mask: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub imask: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub parent: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none", rename = "UDA")]
-    pub uda: Option<HashMap<String, Value>>,
-}
-
 pub fn fetch_task_from_cmd(task_query: &TaskQuery) -> Result<String, anyhow::Error> {
     let mut task = task_query.build();
     trace!("{:?}", task.get_args());
@@ -153,6 +113,7 @@ async fn parse_and_apply_additional_command_fragments<S: Storage>(
         }
     };
     debug!("Arguments: {:?}", fragments);
+    let mut end_given = false;
     for fragment in fragments {
         let b1 = fragment.split_once(':').map_or_else(
             || (fragment.trim().to_string(), None),
@@ -170,14 +131,16 @@ async fn parse_and_apply_additional_command_fragments<S: Storage>(
             task_apply_description(task, ops, validation_result, b1);
         } else if b1.0.to_lowercase().trim() == "priority" {
             task_apply_priority(task, ops, validation_result, b1);
-        } else if ["entry", "wait", "due"].contains(&b1.0.to_lowercase().trim()) {
+        } else if ["entry", "wait", "due", "scheduled", "until", "end"]
+            .contains(&b1.0.to_lowercase().trim())
+        {
+            end_given |= b1.0.to_lowercase().trim() == "end";
             task_apply_timestamps(task, ops, validation_result, b1);
         } else if b1.0.to_lowercase().trim() == "status" {
             task_apply_status(task, ops, validation_result, b1);
         } else if b1.0.to_lowercase().trim() == "recur" {
             task_apply_recur(task, ops, validation_result, b1);
-        } else if ["start", "stop", "done", "end", "modified"].contains(&b1.0.to_lowercase().trim())
-        {
+        } else if ["start", "stop", "done", "modified"].contains(&b1.0.to_lowercase().trim()) {
             validation_result.push(FieldError {
                 field: "additional".into(),
                 message: format!("Manual modification of the field {} is not allowed.", b1.0),
@@ -202,6 +165,9 @@ async fn parse_and_apply_additional_command_fragments<S: Storage>(
             }
         }
     }
+    if end_given {
+        task_validate_end(task, validation_result);
+    }
 }
 
 /// Create a new task
@@ -210,7 +176,9 @@ async fn parse_and_apply_additional_command_fragments<S: Storage>(
 /// The data will be evaluated and a response will be provided via `FormValidation`.
 pub async fn task_add(task: &NewTask, app_state: &AppState) -> Result<Uuid, FormValidation> {
     let mut validation_result = FormValidation::default();
-    let mut replica = get_replica(&app_state.task_storage_path)
+    let mut replica = app_state
+        .replica
+        .write()
         .await
         .map_err(<anyhow::Error as Into<FormValidation>>::into)?;
     let uuid = Uuid::new_v4();
@@ -254,6 +222,7 @@ pub async fn task_add(task: &NewTask, app_state: &AppState) -> Result<Uuid, Form
     }
 
     extract_tags_for_task_add(task, &mut validation_result, &mut ops, &mut new_task);
+    extract_timestamps_for_task_add(task, &mut validation_result, &mut ops, &mut new_task);
 
     if let Some(mut project) = task.project().clone() {
         if project.starts_with("project:") {
@@ -282,18 +251,16 @@ pub async fn task_add(task: &NewTask, app_state: &AppState) -> Result<Uuid, Form
     }
 
     if validation_result.is_success() {
+        // Hooks may change the task or reject it before anything is stored.
+        run_hooks_on_task(app_state, &TaskEvent::OnAdd, None, &mut new_task, &mut ops).await?;
         // Commit those operations to storage.
         match replica.commit_operations(ops).await {
             Ok(()) => {
+                drop(replica);
                 info!("New task {} added", uuid.to_string());
-                // execute hooks.
-                let ct: crate::backend::task::Task = new_task.into();
-                execute_hooks(
-                    &app_state.task_hooks_path,
-                    &TaskEvent::OnAdd,
-                    &None,
-                    &Some(ct),
-                );
+                let new_task: crate::backend::task::Task = new_task.into();
+                run_exit_hooks(app_state, std::slice::from_ref(&new_task)).await;
+                queue_webhooks(app_state, None, &new_task);
                 Ok(uuid)
             }
             Err(e) => {
@@ -310,6 +277,33 @@ pub async fn task_add(task: &NewTask, app_state: &AppState) -> Result<Uuid, Form
     }
 }
 
+/// Sets `scheduled` and `until` of the form, the additional options may still override them.
+fn extract_timestamps_for_task_add(
+    task: &NewTask,
+    validation_result: &mut FormValidation,
+    ops: &mut Vec<Operation>,
+    new_task: &mut taskchampion::Task,
+) {
+    for (property, value) in [("scheduled", task.scheduled()), ("until", task.until())] {
+        let Some(value) = value.as_deref().filter(|p| !p.trim().is_empty()) else {
+            continue;
+        };
+        let result = parse_date_expr(value, Utc::now())
+            .map_err(|p| format!("Failed parsing timestamp for {property} ({p})."))
+            .and_then(|(date, _)| {
+                new_task
+                    .set_timestamp(property, Some(date), ops)
+                    .map_err(|p| format!("Failed setting timestamp for {property} ({p})."))
+            });
+        if let Err(message) = result {
+            validation_result.push(FieldError {
+                field: property.to_string(),
+                message,
+            });
+        }
+    }
+}
+
 fn extract_tags_for_task_add(
     task: &NewTask,
     validation_result: &mut FormValidation,
@@ -351,7 +345,9 @@ pub async fn run_modify_command(
     app_state: &AppState,
 ) -> Result<(), FormValidation> {
     let mut validation_result = FormValidation::default();
-    let mut replica = get_replica(&app_state.task_storage_path)
+    let mut replica = app_state
+        .replica
+        .write()
         .await
         .map_err(<anyhow::Error as Into<FormValidation>>::into)?;
     let mut ops = Operations::new();
@@ -375,75 +371,262 @@ pub async fn run_modify_command(
     if !validation_result.is_success() {
         return Err(validation_result);
     }
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut existing_task,
+        &mut ops,
+    )
+    .await?;
     info!("Updated task {}", uuid.to_string());
     // Commit successful operations to storage.
-    replica
-        .commit_operations(ops)
+    replica.commit_operations(ops).await.map_err(|e| {
+        error!(
+            "Could not update task {}, error: {}",
+            uuid.to_string(),
+            e.to_string()
+        );
+        FormValidation::from(e)
+    })?;
+    drop(replica);
+    let existing_task: crate::backend::task::Task = existing_task.into();
+    run_exit_hooks(app_state, std::slice::from_ref(&existing_task)).await;
+    queue_webhooks(app_state, Some(&old_task.into()), &existing_task);
+    Ok(())
+}
+
+/// Applies a JSON merge patch (RFC 7396) to a task, like `{"priority": "H", "due": null}`.
+/// Dates are given like `20250501T120000Z`, computed properties like `id`,
+/// `urgency` or `modified` are ignored.
+pub async fn patch_task(
+    uuid: Uuid,
+    patch: &serde_json::Value,
+    app_state: &AppState,
+) -> Result<(), FormValidation> {
+    let mut validation_result = FormValidation::default();
+    let mut replica = app_state
+        .replica
+        .write()
         .await
-        .map(|()| {
-            // execute hooks.
-            let ct: crate::backend::task::Task = existing_task.into();
-            execute_hooks(
-                &app_state.task_hooks_path,
-                &TaskEvent::OnModify,
-                &Some(old_task.into()),
-                &Some(ct),
-            );
-        })
-        .map_err(|e| {
-            error!(
-                "Could not update task {}, error: {}",
-                uuid.to_string(),
-                e.to_string()
-            );
-            e.into()
-        })
+        .map_err(<anyhow::Error as Into<FormValidation>>::into)?;
+    let mut existing_task = replica
+        .get_task(uuid)
+        .await?
+        .ok_or_else(|| FormValidation::with_error("Failed to get task"))?;
+
+    let old_task = existing_task.clone();
+    let before: crate::backend::task::Task = old_task.clone().into();
+    let mut patched =
+        serde_json::to_value(&before).map_err(|e| FormValidation::with_error(&e.to_string()))?;
+    merge_patch(&mut patched, patch);
+    let after: crate::backend::task::Task = match serde_json::from_value(patched) {
+        Ok(task) => task,
+        Err(e) => {
+            validation_result.push(FieldError {
+                field: String::from("patch"),
+                message: e.to_string(),
+            });
+            return Err(validation_result);
+        }
+    };
+    if after.uuid != before.uuid {
+        validation_result.push(FieldError {
+            field: String::from("uuid"),
+            message: String::from("The uuid of a task cannot be changed."),
+        });
+    }
+    // invalid dates are read as missing, which would remove them.
+    for (property, value) in [
+        ("entry", after.entry),
+        ("start", after.start),
+        ("end", after.end),
+        ("due", after.due),
+        ("wait", after.wait),
+        ("scheduled", after.scheduled),
+        ("until", after.until),
+    ] {
+        if value.is_none()
+            && patch
+                .get(property)
+                .is_some_and(serde_json::Value::is_string)
+        {
+            validation_result.push(FieldError {
+                field: property.to_string(),
+                message: String::from("Dates are given like 20250501T120000Z."),
+            });
+        }
+    }
+    if !validation_result.is_success() {
+        return Err(validation_result);
+    }
+
+    let mut ops = Operations::new();
+    ops.push(taskchampion::Operation::UndoPoint);
+    apply_hook_changes(&mut existing_task, &before, &after, &mut ops)?;
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut existing_task,
+        &mut ops,
+    )
+    .await?;
+    replica.commit_operations(ops).await.map_err(|e| {
+        error!(
+            "Could not patch task {}, error: {}",
+            uuid.to_string(),
+            e.to_string()
+        );
+        FormValidation::from(e)
+    })?;
+    drop(replica);
+    info!("Patched task {}", uuid.to_string());
+    let existing_task: crate::backend::task::Task = existing_task.into();
+    run_exit_hooks(app_state, std::slice::from_ref(&existing_task)).await;
+    queue_webhooks(app_state, Some(&before), &existing_task);
+    Ok(())
 }
 
-pub fn task_undo() -> Result<(), anyhow::Error> {
-    match Command::new("task")
-        .arg("rc.confirmation:off")
-        .arg("undo")
-        .output()
-    {
-        Ok(_o) => {
-            info!("Task undo success");
-            Ok(())
+/// Merges `patch` into `target` as described in RFC 7396,
+/// `null` removes a property.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let serde_json::Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(
+                    target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
         }
-        Err(e) => {
-            error!("Failed to execute undo: {}", e);
-            anyhow::bail!("Failed to undo");
+    }
+}
+
+/// Reverts the last `steps` undo points of the replica.
+/// Returns the number of undo points which were reverted.
+pub async fn task_undo(steps: usize, app_state: &AppState) -> Result<usize, anyhow::Error> {
+    let (reverted, changed) = undo_operations(&app_state.replica, steps).await?;
+    info!("Task undo reverted {} of {} undo points", reverted, steps);
+    if reverted > 0 {
+        let mut old_tasks = vec![];
+        let mut tasks = vec![];
+        for (uuid, old_task) in changed {
+            // tasks created by the reverted changes are gone.
+            if let Some(task) = get_task(&app_state.replica, uuid).await? {
+                old_tasks.push(old_task);
+                tasks.push(task);
+            }
+        }
+        run_exit_hooks(app_state, &tasks).await;
+        for (old_task, task) in old_tasks.iter().zip(&tasks) {
+            queue_webhooks(app_state, old_task.as_ref(), task);
         }
     }
+    Ok(reverted)
 }
 
-// what would happen
-pub fn list_tasks(
+/// Lists all tasks matching the given query.
+///
+/// Tasks are read from the taskchampion replica and filtered natively,
+/// unless `use_task_cli` is configured, in which case `task export` is used.
+/// An invalid filter is returned as `FormValidation` error for the `filter` field.
+pub async fn list_tasks(
     task_query: &TaskQuery,
+    app_state: &AppState,
 ) -> Result<IndexMap<TaskUUID, crate::backend::task::Task>, anyhow::Error> {
-    read_task_file(task_query)
+    if app_state.app_config.use_task_cli {
+        return read_task_file(task_query);
+    }
+    let task_filter = TaskFilter::from_query(task_query).map_err(|e| {
+        let mut validation = FormValidation::default();
+        validation.push(FieldError {
+            field: String::from("filter"),
+            message: e.to_string(),
+        });
+        validation
+    })?;
+    query_tasks(&task_filter, task_query.report(), app_state).await
 }
 
-pub fn run_annotate_command(task_uuid: Uuid, annotation: &str) -> Result<(), anyhow::Error> {
-    let mut task_cmd = Command::new("task");
-    task_cmd.arg("annotate").arg(task_uuid.to_string());
-    annotation.split(' ').for_each(|v| {
-        task_cmd.arg(v);
-    });
-    if let Err(e) = task_cmd.output() {
-        error!("Failed to execute command: {}", e);
-        anyhow::bail!("Failed to execute annotation command");
+/// Evaluates the filter against all tasks of the replica,
+/// sorted the way the report does.
+async fn query_tasks(
+    task_filter: &TaskFilter,
+    report: &TaskReport,
+    app_state: &AppState,
+) -> Result<IndexMap<TaskUUID, crate::backend::task::Task>, anyhow::Error> {
+    let now = Utc::now();
+    let coefficients = &app_state.app_config.urgency;
+    let mut tasks: Vec<crate::backend::task::Task> = get_tasks(&app_state.replica)
+        .await?
+        .into_iter()
+        .map(|mut task| {
+            task.urgency = Some(coefficients.urgency(&task, now));
+            task
+        })
+        .filter(|task| task_filter.matches(task, now))
+        .collect();
+    report.sort(&mut tasks);
+    if let Some(limit) = task_filter.limit() {
+        tasks.truncate(limit);
     }
-    Ok(())
+    let mut hm = IndexMap::new();
+    for task in tasks {
+        hm.insert(TaskUUID(task.uuid.to_string()), task);
+    }
+    Ok(hm)
 }
 
-pub fn run_denotate_command(task_uuid: Uuid) -> Result<(), anyhow::Error> {
-    let mut task_cmd = Command::new("task");
-    task_cmd.arg(task_uuid.to_string()).arg("denotate");
-    if let Err(e) = task_cmd.output() {
-        error!("Failed to execute command: {}", e);
-        anyhow::bail!("Failed to execute denotate command");
+/// Adds the annotation to the task, the text is stored as given.
+pub async fn run_annotate_command(
+    task_uuid: Uuid,
+    annotation: &str,
+    app_state: &AppState,
+) -> Result<(), FormValidation> {
+    let annotation = annotation.trim();
+    if annotation.is_empty() {
+        let mut validation_result = FormValidation::default();
+        validation_result.push(FieldError {
+            field: "annotation".into(),
+            message: "An annotation requires a description.".into(),
+        });
+        return Err(validation_result);
     }
+    annotate_task(task_uuid, annotation, app_state).await?;
+    Ok(())
+}
+
+/// Removes the most recent annotation of the task.
+pub async fn run_denotate_command(
+    task_uuid: Uuid,
+    app_state: &AppState,
+) -> Result<(), FormValidation> {
+    let task = get_task(&app_state.replica, task_uuid)
+        .await?
+        .ok_or_else(|| FormValidation::with_error("Failed to get task"))?;
+    let Some(annotation) = task
+        .annotations
+        .as_ref()
+        .and_then(|p| p.iter().max_by_key(|a| *a.entry()))
+    else {
+        let mut validation_result = FormValidation::default();
+        validation_result.push(FieldError {
+            field: "annotation".into(),
+            message: "The task has no annotations.".into(),
+        });
+        return Err(validation_result);
+    };
+    denotate_task(task_uuid, annotation, app_state).await?;
     Ok(())
 }
 
@@ -454,7 +637,7 @@ pub async fn change_task_status(
     task: TaskUpdateStatus,
     app_state: &AppState,
 ) -> Result<(), anyhow::Error> {
-    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let mut replica = app_state.replica.write().await?;
     let mut ops = Operations::new();
     ops.push(taskchampion::Operation::UndoPoint);
 
@@ -471,20 +654,23 @@ pub async fn change_task_status(
     }
 
     t.set_status(task_status, &mut ops)?;
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut t,
+        &mut ops,
+    )
+    .await?;
 
     // Commit those operations to storage.
     match replica.commit_operations(ops).await {
         Ok(()) => {
+            drop(replica);
             info!("Task {} completed", task.uuid.to_string());
-
-            // execute hooks.
-            let ct: crate::backend::task::Task = t.into();
-            execute_hooks(
-                &app_state.task_hooks_path,
-                &TaskEvent::OnModify,
-                &Some(old_task.into()),
-                &Some(ct),
-            );
+            let t: crate::backend::task::Task = t.into();
+            run_exit_hooks(app_state, std::slice::from_ref(&t)).await;
+            queue_webhooks(app_state, Some(&old_task.into()), &t);
             Ok(())
         }
         Err(e) => {
@@ -498,23 +684,14 @@ pub async fn change_task_status(
     }
 }
 
-pub fn fetch_active_task() -> Result<Option<Task>, anyhow::Error> {
-    // maybe another task is running? So stop all other tasks first
-    match Command::new("task").arg("+ACTIVE").arg("export").output() {
-        Err(e) => {
-            error!("No active task found: {}", e);
-            anyhow::bail!("No active task found");
-        }
-        Ok(v) => {
-            let n = String::from_utf8(v.stdout)?;
-            let res: Vec<Task> = serde_json::from_str(&n)?;
-            if res.is_empty() {
-                Ok(None)
-            } else {
-                Ok(res.first().cloned())
-            }
-        }
-    }
+/// Retrieves the currently running task, if any.
+pub async fn fetch_active_task(
+    app_state: &AppState,
+) -> Result<Option<crate::backend::task::Task>, anyhow::Error> {
+    let mut task_query = TaskQuery::empty();
+    task_query.set_filter("+ACTIVE");
+    let tasks = list_tasks(&task_query, app_state).await?;
+    Ok(tasks.into_values().next())
 }
 
 pub async fn toggle_task_active(
@@ -522,7 +699,7 @@ pub async fn toggle_task_active(
     task_status: String,
     app_state: &AppState,
 ) -> Result<bool, anyhow::Error> {
-    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let mut replica = app_state.replica.write().await?;
     let mut ops = Operations::new();
     ops.push(taskchampion::Operation::UndoPoint);
 
@@ -531,43 +708,59 @@ pub async fn toggle_task_active(
     };
 
     let old_task = t.clone();
-    let mut changed_tasks: Vec<(taskchampion::Task, taskchampion::Task)> = Vec::new();
+    let mut changed_tasks: Vec<crate::backend::task::Task> = Vec::new();
+    let mut old_tasks: Vec<crate::backend::task::Task> = Vec::new();
 
-    // Request to stop the job
-    if task_status == "stop" {
+    // Request to stop the job, an active job is restarted.
+    if task_status == "stop" || t.is_active() {
         t.stop(&mut ops)?;
     }
 
-    // Stop all active tasks.
+    // Stop all other active tasks, the job itself gets its hooks below.
     for mut single_task in replica.all_tasks().await? {
-        if single_task.1.is_active() {
-            let old = single_task.1.clone();
-            single_task.1.stop(&mut ops)?;
-            let new = single_task.1.clone();
-            changed_tasks.push((old, new));
+        if single_task.0 == task_uuid || !single_task.1.is_active() {
+            continue;
         }
+        let old = single_task.1.clone();
+        single_task.1.stop(&mut ops)?;
+        run_hooks_on_task(
+            app_state,
+            &TaskEvent::OnModify,
+            Some(&old),
+            &mut single_task.1,
+            &mut ops,
+        )
+        .await?;
+        old_tasks.push(old.into());
+        changed_tasks.push(single_task.1.into());
     }
 
     // Request to start the job
     if task_status == "start" {
         t.start(&mut ops)?;
     }
-    changed_tasks.push((old_task, t.clone()));
+    run_hooks_on_task(
+        app_state,
+        &TaskEvent::OnModify,
+        Some(&old_task),
+        &mut t,
+        &mut ops,
+    )
+    .await?;
 
     // Commit those operations to storage.
     match replica.commit_operations(ops).await {
         Ok(()) => {
+            drop(replica);
             info!("Task {} started", task_uuid.to_string());
-            // execute hooks.
-            for ct in changed_tasks {
-                execute_hooks(
-                    &app_state.task_hooks_path,
-                    &TaskEvent::OnModify,
-                    &Some(ct.0.into()),
-                    &Some(ct.1.into()),
-                );
+            let is_active = t.is_active();
+            old_tasks.push(old_task.into());
+            changed_tasks.push(t.into());
+            run_exit_hooks(app_state, &changed_tasks).await;
+            for (old, new) in old_tasks.iter().zip(&changed_tasks) {
+                queue_webhooks(app_state, Some(old), new);
             }
-            Ok(t.is_active())
+            Ok(is_active)
         }
         Err(e) => {
             error!(
@@ -581,17 +774,27 @@ pub async fn toggle_task_active(
 }
 
 /// Read / Retrieve task by UUID
-/// via task command line.
-/// This is required required in order to get
-/// priority information.
-pub fn get_task_details(uuid_str: String) -> Result<crate::backend::task::Task, anyhow::Error> {
-    debug!("uuid: {}", uuid_str);
-    let mut task_query = TaskQuery::empty();
-    task_query.set_filter(&uuid_str);
-    let tasks = read_task_file(&task_query)?;
-    match tasks.get(&TaskUUID(uuid_str)) {
+/// from the replica, or via task command line if `use_task_cli` is configured.
+pub async fn get_task_details(
+    uuid: Uuid,
+    app_state: &AppState,
+) -> Result<crate::backend::task::Task, anyhow::Error> {
+    debug!("uuid: {}", uuid);
+    if app_state.app_config.use_task_cli {
+        let mut task_query = TaskQuery::empty();
+        task_query.set_filter(&uuid.to_string());
+        let tasks = read_task_file(&task_query)?;
+        return match tasks.get(&TaskUUID(uuid.to_string())) {
+            None => anyhow::bail!("Matching task not found"),
+            Some(t) => Ok(t.clone()),
+        };
+    }
+    match get_task(&app_state.replica, uuid).await? {
         None => anyhow::bail!("Matching task not found"),
-        Some(t) => Ok(t.clone()),
+        Some(mut t) => {
+            t.urgency = Some(app_state.app_config.urgency.urgency(&t, Utc::now()));
+            Ok(t)
+        }
     }
 }
 
@@ -611,7 +814,7 @@ pub async fn get_task_details_form(
     let mut tasks_deps: Vec<crate::backend::task::Task> = Vec::new();
     if let Some(dep_list) = &task.depends {
         for dep_uuid in dep_list {
-            if let Ok(Some(dep_task)) = get_task(&app_state.task_storage_path, *dep_uuid).await {
+            if let Ok(Some(dep_task)) = get_task(&app_state.replica, *dep_uuid).await {
                 tasks_deps.push(dep_task);
             }
         }
@@ -656,40 +859,75 @@ pub async fn get_task_details_form(
     tasks_deps
 }
 
+/// One shortcut per annotation of the task, used to select
+/// the annotation to remove or edit.
+fn annotation_shortcuts(task: &crate::backend::task::Task) -> Vec<String> {
+    let mut shortcuts: HashSet<String> = HashSet::new();
+    task.annotations
+        .as_ref()
+        .map(|anno_list| {
+            anno_list
+                .iter()
+                .map(|_| make_shortcut(&mut shortcuts))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Context to render `task_details.html` of the task.
+pub async fn task_details_context(
+    task: &mut crate::backend::task::Task,
+    app_state: &AppState,
+) -> Context {
+    let tasks_deps = get_task_details_form(task, app_state).await;
+    let mut ctx: Context = get_default_context(app_state);
+    ctx.insert("tasks_db", &tasks_deps);
+    ctx.insert("annotate_shortcuts", &annotation_shortcuts(task));
+    // how the urgency was computed, not available for `task export`
+    if !app_state.app_config.use_task_cli {
+        let urgency_terms = app_state.app_config.urgency.terms(task, Utc::now());
+        ctx.insert("urgency_terms", &urgency_terms);
+    }
+    ctx.insert("task", task);
+    ctx.insert(
+        "STYLESHEET_URL",
+        DIST_CONTENT
+            .get_file("style.css")
+            .unwrap()
+            .contents_utf8()
+            .unwrap(),
+    );
+    ctx.insert(
+        "JS_BUNDLE_PATH",
+        DIST_CONTENT
+            .get_file("bundle.js")
+            .unwrap()
+            .contents_utf8()
+            .unwrap(),
+    );
+    ctx
+}
+
 /// Request to display a task detail page.
 pub async fn display_task_details(
     Path(task_id): Path<Uuid>,
     app_state: State<AppState>,
-) -> Response<String> {
-    match get_task_details(task_id.to_string()) {
+) -> Result<Response<String>, AppError> {
+    Ok(match get_task_details(task_id, &app_state).await {
         Ok(mut task) => {
-            let tasks_deps = get_task_details_form(&mut task, &app_state).await;
-            let mut ctx: Context = get_default_context(&app_state);
-            ctx.insert("tasks_db", &tasks_deps);
-            let mut shortcuts: HashSet<String> = HashSet::new();
-            let mut shortcut_list: Vec<String> = Vec::new();
-            if let Some(anno_list) = &task.annotations {
-                for _ in anno_list.iter().enumerate() {
-                    let shortcut = make_shortcut(&mut shortcuts);
-                    shortcut_list.push(shortcut);
-                }
-            }
-            // annotate_shortcuts
-            ctx.insert("annotate_shortcuts", &shortcut_list);
-            ctx.insert("task", &task);
-            ctx.insert("STYLESHEET_URL", DIST_CONTENT.get_file("style.css").unwrap().contents_utf8().unwrap());
-            ctx.insert("JS_BUNDLE_PATH", DIST_CONTENT.get_file("bundle.js").unwrap().contents_utf8().unwrap());
+            let ctx = task_details_context(&mut task, &app_state).await;
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, "text/html")
                 .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
                 .unwrap()
         }
+        Err(e) if is_database_busy(&e) => return Err(e.into()),
         Err(_) => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(String::new())
             .unwrap(),
-    }
+    })
 }
 
 /// Request to get a confirmation screen prior
@@ -697,8 +935,8 @@ pub async fn display_task_details(
 pub async fn display_task_delete(
     Path(task_id): Path<Uuid>,
     app_state: State<AppState>,
-) -> Response<String> {
-    match get_task_details(task_id.to_string()) {
+) -> Result<Response<String>, AppError> {
+    Ok(match get_task_details(task_id, &app_state).await {
         Ok(mut task) => {
             let tasks_deps = get_task_details_form(&mut task, &app_state).await;
             let mut ctx: Context = get_default_context(&app_state);
@@ -714,11 +952,12 @@ pub async fn display_task_delete(
                 .body(TEMPLATES.render("task_delete_confirm.html", &ctx).unwrap())
                 .unwrap()
         }
+        Err(e) if is_database_busy(&e) => return Err(e.into()),
         Err(_) => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(String::new())
             .unwrap(),
-    }
+    })
 }
 
 /// Process request to delete a specific annotation entry
@@ -728,33 +967,67 @@ pub async fn api_denotate_task_entry(
     app_state: State<AppState>,
     Form(denotate_form): Form<Annotation>,
 ) -> Response<String> {
-    match get_task(&app_state.task_storage_path, task_id).await {
-        Ok(t) if t.is_some() => match denotate_task(task_id, &denotate_form, &app_state).await {
-            Ok(mut task) => {
-                let tasks_deps = get_task_details_form(&mut task, &app_state).await;
-                let mut ctx: Context = get_default_context(&app_state);
-                ctx.insert("tasks_db", &tasks_deps);
-                // annotate_shortcuts
-                ctx.insert("annotate_shortcuts", "");
-                ctx.insert("task", &task);
-                ctx.insert("STYLESHEET_URL", DIST_CONTENT.get_file("style.css").unwrap().contents_utf8().unwrap());
-                ctx.insert("JS_BUNDLE_PATH", DIST_CONTENT.get_file("bundle.js").unwrap().contents_utf8().unwrap());
-                Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, "text/html")
-                    .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
-                    .unwrap()
-            }
-            Err(_) => Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(String::new())
-                .unwrap(),
-        },
-        Ok(_) | Err(_) => Response::builder()
+    let Ok(mut task) = get_task_details(task_id, &app_state).await else {
+        return Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(String::new())
-            .unwrap(),
+            .unwrap();
+    };
+    let mut validation_result = FormValidation::default();
+    match denotate_task(task_id, &denotate_form, &app_state).await {
+        Ok(updated_task) => task = updated_task,
+        Err(e) => validation_result = e.into(),
+    }
+    let mut ctx = task_details_context(&mut task, &app_state).await;
+    if !validation_result.is_success() {
+        ctx.insert("validation", &validation_result);
+    }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/html")
+        .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
+        .unwrap()
+}
+
+/// Process request to change the description of a specific
+/// annotation entry of the task.
+pub async fn api_edit_task_annotation(
+    Path(task_id): Path<Uuid>,
+    app_state: State<AppState>,
+    Form(annotation_form): Form<Annotation>,
+) -> Response<String> {
+    let Ok(mut task) = get_task_details(task_id, &app_state).await else {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(String::new())
+            .unwrap();
+    };
+    let description = annotation_form.description().trim();
+    let mut validation_result = FormValidation::default();
+    if description.is_empty() {
+        validation_result.push(FieldError {
+            field: "annotation".into(),
+            message: "An annotation requires a description.".into(),
+        });
+    } else {
+        let annotation = Annotation::new(*annotation_form.entry(), description);
+        match edit_annotation(task_id, &annotation, &app_state).await {
+            Ok(_) => match get_task_details(task_id, &app_state).await {
+                Ok(updated_task) => task = updated_task,
+                Err(e) => validation_result = e.into(),
+            },
+            Err(e) => validation_result = e.into(),
+        }
+    }
+    let mut ctx = task_details_context(&mut task, &app_state).await;
+    if !validation_result.is_success() {
+        ctx.insert("validation", &validation_result);
     }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/html")
+        .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
+        .unwrap()
 }
 
 pub const TAG_KEYWORDS: [&str; 4] = ["next", "pending", "completed", "new"];
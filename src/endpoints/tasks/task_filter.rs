@@ -0,0 +1,720 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+use anyhow::bail;
+use chrono::{DateTime, Days, Utc};
+use taskchampion::Status;
+
+use super::task_query_builder::{TaskPriority, TaskQuery, TaskStatus};
+use crate::backend::task::{Task, convert_task_status};
+use crate::core::date_expr::parse_date_expr;
+
+/// Attributes known to taskwarrior, used to resolve abbreviations like `pro:Home`.
+const ATTRIBUTES: [&str; 19] = [
+    "description",
+    "project",
+    "priority",
+    "status",
+    "tags",
+    "uuid",
+    "id",
+    "entry",
+    "due",
+    "wait",
+    "scheduled",
+    "until",
+    "start",
+    "end",
+    "modified",
+    "recur",
+    "parent",
+    "urgency",
+    "limit",
+];
+
+const DATE_ATTRIBUTES: [&str; 8] = [
+    "entry",
+    "due",
+    "wait",
+    "scheduled",
+    "until",
+    "start",
+    "end",
+    "modified",
+];
+
+/// Modifier of an attribute, like `due.before:2025-05-01` or `project.not:Home`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Before,
+    After,
+    Is,
+    Not,
+    Has,
+    Hasnt,
+    StartsWith,
+    EndsWith,
+    Any,
+    None,
+}
+
+impl Modifier {
+    /// Parses a modifier name, including taskwarrior's aliases like `.below` or `.isnt`.
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "before" | "below" | "under" => Some(Self::Before),
+            "after" | "above" | "over" => Some(Self::After),
+            "is" | "equals" => Some(Self::Is),
+            "not" | "isnt" => Some(Self::Not),
+            "has" | "contains" => Some(Self::Has),
+            "hasnt" => Some(Self::Hasnt),
+            "startswith" | "left" => Some(Self::StartsWith),
+            "endswith" | "right" => Some(Self::EndsWith),
+            "any" => Some(Self::Any),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// A single term of a task filter, e.g. `+home`, `project:Work` or `groceries`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterTerm {
+    HasTag(String),
+    HasNotTag(String),
+    Attribute(String, String),
+    Date {
+        attribute: String,
+        date: DateTime<Utc>,
+        whole_day: bool,
+    },
+    /// `attribute.modifier:value`, the value of date attributes is parsed up front.
+    Modified {
+        attribute: String,
+        modifier: Modifier,
+        value: String,
+        date: Option<(DateTime<Utc>, bool)>,
+    },
+    /// Id ranges, kept as ranges so a huge range costs no memory.
+    Ids(Vec<RangeInclusive<i64>>),
+    Uuid(String),
+    Pattern(String),
+}
+
+/// Boolean expression of filter terms. Adjacent terms are joined with `and`,
+/// which binds stronger than `xor`, which binds stronger than `or`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterExpr {
+    Term(FilterTerm),
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Xor(Box<FilterExpr>, Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    pub fn matches(&self, task: &Task, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Term(term) => term_matches(term, task, now),
+            Self::And(operands) => operands.iter().all(|p| p.matches(task, now)),
+            Self::Or(operands) => operands.iter().any(|p| p.matches(task, now)),
+            Self::Xor(lhs, rhs) => lhs.matches(task, now) != rhs.matches(task, now),
+        }
+    }
+
+    fn and(mut operands: Vec<Self>) -> Self {
+        if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            Self::And(operands)
+        }
+    }
+}
+
+/// Filter evaluated natively against the tasks of the replica.
+/// All expressions must match for a task to be part of the result.
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    expressions: Vec<FilterExpr>,
+    limit: Option<usize>,
+}
+
+impl TaskFilter {
+    /// Parses a filter like `+home (project:Work or due.before:2025-05-01)`.
+    pub fn parse(filter: &str) -> Result<Self, anyhow::Error> {
+        let mut task_filter = Self::default();
+        task_filter.extend(filter)?;
+        Ok(task_filter)
+    }
+
+    /// Builds the filter for all parts of a `TaskQuery`, including the defaults of its report.
+    pub fn from_query(task_query: &TaskQuery) -> Result<Self, anyhow::Error> {
+        let mut task_filter = Self::default();
+        task_filter.extend(task_query.report().filter())?;
+        match task_query.status() {
+            TaskStatus::Pending => {
+                task_filter.push(FilterTerm::Attribute("status".into(), "pending".into()));
+            }
+            TaskStatus::Completed => {
+                task_filter.push(FilterTerm::Attribute("status".into(), "completed".into()));
+            }
+            TaskStatus::Waiting => {
+                task_filter.push(FilterTerm::Attribute("status".into(), "waiting".into()));
+            }
+            TaskStatus::NotSet => {}
+        }
+        let priority = match task_query.priority() {
+            TaskPriority::High => Some("H"),
+            TaskPriority::Medium => Some("M"),
+            TaskPriority::Low => Some("L"),
+            TaskPriority::NotSet => None,
+        };
+        if let Some(priority) = priority {
+            task_filter.push(FilterTerm::Attribute("priority".into(), priority.into()));
+        }
+        if let Some(project) = task_query.project() {
+            let project = project.strip_prefix("project:").unwrap_or(project);
+            task_filter.push(FilterTerm::Attribute("project".into(), project.into()));
+        }
+        for tag in task_query.tags() {
+            task_filter.extend(tag)?;
+        }
+        if let Some(filter) = task_query.filter() {
+            task_filter.extend(filter)?;
+        }
+        if let Some(new_entry) = task_query.new_entry() {
+            task_filter.extend(new_entry)?;
+        }
+        Ok(task_filter)
+    }
+
+    pub fn push(&mut self, term: FilterTerm) {
+        self.expressions.push(FilterExpr::Term(term));
+    }
+
+    /// Parses the given filter text and adds it as a whole,
+    /// like taskwarrior puts parentheses around each part of a filter.
+    pub fn extend(&mut self, filter: &str) -> Result<(), anyhow::Error> {
+        let tokens = tokenize(filter)?;
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        let mut parser = ExprParser {
+            tokens,
+            position: 0,
+            task_filter: self,
+        };
+        let expression = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {token} in filter {filter}");
+        }
+        match expression {
+            FilterExpr::And(operands) => self.expressions.extend(operands),
+            expression => self.expressions.push(expression),
+        }
+        Ok(())
+    }
+
+    pub fn expressions(&self) -> &Vec<FilterExpr> {
+        &self.expressions
+    }
+
+    /// Maximum number of tasks to return, as given via `limit:N`.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    pub fn matches(&self, task: &Task, now: DateTime<Utc>) -> bool {
+        self.expressions.iter().all(|p| p.matches(task, now))
+    }
+
+    /// Parses a single term. Returns `None` for settings like `limit:N`.
+    fn parse_term(&mut self, word: &str) -> Result<Option<FilterTerm>, anyhow::Error> {
+        let term = if let Some(tag) = word.strip_prefix('+')
+            && !tag.is_empty()
+        {
+            FilterTerm::HasTag(tag.to_string())
+        } else if let Some(tag) = word.strip_prefix('-')
+            && !tag.is_empty()
+            && !tag.contains(':')
+        {
+            FilterTerm::HasNotTag(tag.to_string())
+        } else if let Some((name, value)) = word.split_once(':') {
+            return self.parse_attribute(name, value);
+        } else if let Some(ids) = parse_ids(word) {
+            FilterTerm::Ids(ids)
+        } else if is_uuid_prefix(word) {
+            FilterTerm::Uuid(word.to_lowercase())
+        } else {
+            let pattern = word
+                .strip_prefix('/')
+                .and_then(|p| p.strip_suffix('/'))
+                .unwrap_or(word);
+            FilterTerm::Pattern(pattern.to_string())
+        };
+        Ok(Some(term))
+    }
+
+    fn parse_attribute(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<Option<FilterTerm>, anyhow::Error> {
+        if let Some((name, modifier_name)) = name.split_once('.') {
+            let Some(modifier) = Modifier::parse(modifier_name) else {
+                bail!("Unknown attribute modifier {modifier_name} in {name}.{modifier_name}");
+            };
+            return parse_modified(&resolve_attribute(name), modifier, value).map(Some);
+        }
+        let name = resolve_attribute(name);
+        let term = match name.as_str() {
+            "limit" => {
+                self.limit = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|e| anyhow::anyhow!("Invalid limit {value}: {e}"))?,
+                );
+                return Ok(None);
+            }
+            "tags" | "tag" => FilterTerm::HasTag(value.to_string()),
+            "uuid" => FilterTerm::Uuid(value.to_lowercase()),
+            "id" => {
+                let Some(ids) = parse_ids(value) else {
+                    bail!("Invalid id {value}");
+                };
+                FilterTerm::Ids(ids)
+            }
+            attribute if DATE_ATTRIBUTES.contains(&attribute) && !value.is_empty() => {
+                let Some((date, whole_day)) = parse_date(value) else {
+                    bail!("Failed parsing date {value} for {attribute}");
+                };
+                FilterTerm::Date {
+                    attribute: attribute.to_string(),
+                    date,
+                    whole_day,
+                }
+            }
+            attribute => FilterTerm::Attribute(attribute.to_string(), value.to_string()),
+        };
+        Ok(Some(term))
+    }
+}
+
+fn parse_modified(
+    attribute: &str,
+    modifier: Modifier,
+    value: &str,
+) -> Result<FilterTerm, anyhow::Error> {
+    if attribute == "limit" {
+        bail!("Attribute modifiers are not supported for limit");
+    }
+    if attribute == "tags" || attribute == "tag" {
+        return match modifier {
+            Modifier::Is | Modifier::Has => Ok(FilterTerm::HasTag(value.to_string())),
+            Modifier::Not | Modifier::Hasnt => Ok(FilterTerm::HasNotTag(value.to_string())),
+            Modifier::Any => Ok(FilterTerm::HasTag("TAGGED".into())),
+            Modifier::None => Ok(FilterTerm::HasNotTag("TAGGED".into())),
+            _ => bail!("Attribute modifier {modifier:?} is not supported for tags"),
+        };
+    }
+    let mut date = None;
+    if DATE_ATTRIBUTES.contains(&attribute) {
+        match modifier {
+            Modifier::Any | Modifier::None => {}
+            Modifier::Before | Modifier::After | Modifier::Is | Modifier::Not => {
+                let Some(parsed) = parse_date(value) else {
+                    bail!("Failed parsing date {value} for {attribute}");
+                };
+                date = Some(parsed);
+            }
+            _ => bail!("Attribute modifier {modifier:?} is not supported for {attribute}"),
+        }
+    }
+    Ok(FilterTerm::Modified {
+        attribute: attribute.to_string(),
+        modifier,
+        value: value.to_string(),
+        date,
+    })
+}
+
+/// Splits a filter into words, with parentheses as separate tokens.
+fn tokenize(filter: &str) -> Result<Vec<String>, anyhow::Error> {
+    let mut tokens = vec![];
+    for word in shell_words::split(filter)? {
+        let mut word = word.trim();
+        let mut closing = 0;
+        while let Some(rest) = word.strip_prefix('(') {
+            tokens.push(String::from("("));
+            word = rest;
+        }
+        while let Some(rest) = word.strip_suffix(')') {
+            closing += 1;
+            word = rest;
+        }
+        if !word.is_empty() {
+            tokens.push(word.to_string());
+        }
+        tokens.extend(std::iter::repeat_n(String::from(")"), closing));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser for filter expressions.
+struct ExprParser<'a> {
+    tokens: Vec<String>,
+    position: usize,
+    task_filter: &'a mut TaskFilter,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next_is(&self, operator: &str) -> bool {
+        self.peek()
+            .is_some_and(|p| p.eq_ignore_ascii_case(operator))
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, anyhow::Error> {
+        let mut operands = vec![self.parse_xor()?];
+        while self.next_is("or") {
+            self.position += 1;
+            operands.push(self.parse_xor()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            FilterExpr::Or(operands)
+        })
+    }
+
+    fn parse_xor(&mut self) -> Result<FilterExpr, anyhow::Error> {
+        let mut expression = self.parse_and()?;
+        while self.next_is("xor") {
+            self.position += 1;
+            expression = FilterExpr::Xor(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, anyhow::Error> {
+        let mut operands = vec![];
+        let mut consumed = false;
+        while let Some(token) = self.peek() {
+            if ["or", "xor", ")"].contains(&token.to_lowercase().as_str()) {
+                break;
+            }
+            if token.eq_ignore_ascii_case("and") {
+                if !consumed {
+                    bail!("Missing filter term before {token}");
+                }
+                self.position += 1;
+                if self
+                    .peek()
+                    .is_none_or(|p| ["and", "or", "xor", ")"].contains(&p.to_lowercase().as_str()))
+                {
+                    bail!("Filter operator and is missing a term");
+                }
+                continue;
+            }
+            consumed = true;
+            if token == "(" {
+                self.position += 1;
+                let expression = self.parse_or()?;
+                if self.peek() != Some(")") {
+                    bail!("Missing closing parenthesis in filter");
+                }
+                self.position += 1;
+                operands.push(expression);
+            } else {
+                let word = token.to_string();
+                self.position += 1;
+                if let Some(term) = self.task_filter.parse_term(&word)? {
+                    operands.push(FilterExpr::Term(term));
+                }
+            }
+        }
+        if !consumed {
+            match self.peek() {
+                Some(token) => bail!("Missing filter term before {token}"),
+                None => bail!("Missing filter term at the end of the filter"),
+            }
+        }
+        Ok(FilterExpr::and(operands))
+    }
+}
+
+/// Resolves abbreviated attribute names with at least two characters,
+/// as long as the abbreviation is unique.
+fn resolve_attribute(name: &str) -> String {
+    let name = name.to_lowercase();
+    if ATTRIBUTES.contains(&name.as_str()) || name.len() < 2 {
+        return name;
+    }
+    let mut candidates = ATTRIBUTES.iter().filter(|p| p.starts_with(&name));
+    match (candidates.next(), candidates.next()) {
+        (Some(attribute), None) => (*attribute).to_string(),
+        _ => name,
+    }
+}
+
+/// Parses id lists like `1`, `1,4` or `2-5`.
+fn parse_ids(value: &str) -> Option<Vec<RangeInclusive<i64>>> {
+    if !value.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut ids = vec![];
+    for part in value.split(',') {
+        if let Some((start, end)) = part.split_once('-') {
+            let start = start.parse::<i64>().ok()?;
+            let end = end.parse::<i64>().ok()?;
+            ids.push(start..=end);
+        } else {
+            let id = part.parse::<i64>().ok()?;
+            ids.push(id..=id);
+        }
+    }
+    Some(ids)
+}
+
+fn is_uuid_prefix(value: &str) -> bool {
+    value.len() >= 8
+        && value.len() <= 36
+        && value.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+        && value.chars().take(8).all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parses date expressions like `2025-05-01`, `eow` or `+3d`.
+/// Returns whether only a day was given.
+fn parse_date(value: &str) -> Option<(DateTime<Utc>, bool)> {
+    parse_date_expr(value, Utc::now()).ok()
+}
+
+fn task_date(task: &Task, attribute: &str) -> Option<DateTime<Utc>> {
+    match attribute {
+        "entry" => task.entry,
+        "due" => task.due,
+        "wait" => task.wait,
+        "scheduled" => task.scheduled,
+        "until" => task.until,
+        "start" => task.start,
+        "end" => task.end,
+        "modified" => task.modified,
+        _ => None,
+    }
+}
+
+fn is_pending(task: &Task) -> bool {
+    task.status.as_ref().is_some_and(|p| *p == Status::Pending)
+}
+
+/// Checks user tags as well as synthetic and virtual tags like `ACTIVE` or `OVERDUE`.
+fn has_tag(task: &Task, tag: &str, now: DateTime<Utc>) -> bool {
+    if task
+        .tags
+        .as_ref()
+        .is_some_and(|p| p.iter().any(|t| t == tag))
+        || task.synthetic_tags.iter().any(|t| t == tag)
+    {
+        return true;
+    }
+    let today = now.date_naive();
+    match tag {
+        "TAGGED" => task.tags.as_ref().is_some_and(|p| !p.is_empty()),
+        "ANNOTATED" => task.annotations.as_ref().is_some_and(|p| !p.is_empty()),
+        "PROJECT" => task.project.as_ref().is_some_and(|p| !p.is_empty()),
+        "PRIORITY" => task.priority.as_ref().is_some_and(|p| !p.is_empty()),
+        "SCHEDULED" => task.scheduled.is_some(),
+        "UNTIL" => task.until.is_some(),
+        "UDA" => task.uda.as_ref().is_some_and(|p| !p.is_empty()),
+        "OVERDUE" => is_pending(task) && task.due.is_some_and(|p| p < now),
+        "TODAY" => task.due.is_some_and(|p| p.date_naive() == today),
+        "TOMORROW" => task
+            .due
+            .is_some_and(|p| today.checked_add_days(Days::new(1)) == Some(p.date_naive())),
+        "DUE" => {
+            is_pending(task)
+                && task
+                    .due
+                    .is_some_and(|p| p >= now && p <= now + chrono::Duration::days(7))
+        }
+        "READY" => {
+            is_pending(task)
+                && !task
+                    .synthetic_tags
+                    .iter()
+                    .any(|t| t == "BLOCKED" || t == "WAITING")
+                && task.scheduled.is_none_or(|p| p <= now)
+        }
+        _ => false,
+    }
+}
+
+fn attribute_matches(task: &Task, attribute: &str, value: &str) -> bool {
+    match attribute {
+        "status" => match value {
+            "waiting" => is_pending(task) && task.synthetic_tags.iter().any(|t| t == "WAITING"),
+            _ => task
+                .status
+                .as_ref()
+                .is_some_and(|p| *p == convert_task_status(value)),
+        },
+        "project" => {
+            let project = task.project.clone().unwrap_or_default();
+            if value.is_empty() {
+                project.is_empty()
+            } else {
+                project.starts_with(value)
+            }
+        }
+        "priority" => task.priority.clone().unwrap_or_default() == value,
+        "description" => task.description.contains(value),
+        "recur" => task.recur.clone().unwrap_or_default() == value,
+        "parent" => task.parent.clone().unwrap_or_default() == value,
+        attribute if DATE_ATTRIBUTES.contains(&attribute) => task_date(task, attribute).is_none(),
+        attribute => attribute_value(task, attribute) == value,
+    }
+}
+
+/// Value of a non-date attribute as text, empty if not set.
+fn attribute_value(task: &Task, attribute: &str) -> String {
+    match attribute {
+        "description" => task.description.clone(),
+        "project" => task.project.clone().unwrap_or_default(),
+        "priority" => task.priority.clone().unwrap_or_default(),
+        "status" => match task.status.as_ref() {
+            Some(Status::Pending) => String::from("pending"),
+            Some(Status::Completed) => String::from("completed"),
+            Some(Status::Deleted) => String::from("deleted"),
+            Some(Status::Recurring) => String::from("recurring"),
+            Some(Status::Unknown(status)) => status.clone(),
+            None => String::new(),
+        },
+        "recur" => task.recur.clone().unwrap_or_default(),
+        "parent" => task.parent.clone().unwrap_or_default(),
+        "uuid" => task.uuid.to_string(),
+        "id" => task.id.map(|p| p.to_string()).unwrap_or_default(),
+        "urgency" => task.urgency.map(|p| p.to_string()).unwrap_or_default(),
+        uda => task
+            .uda
+            .as_ref()
+            .and_then(|p| p.get(uda))
+            .cloned()
+            .unwrap_or_default(),
+    }
+}
+
+/// Orders attribute values for `.before` and `.after`:
+/// numbers numerically, priorities from L to H, everything else alphabetically.
+fn compare_values(attribute: &str, lhs: &str, rhs: &str) -> Ordering {
+    if attribute == "priority" {
+        let rank = |p: &str| ["", "L", "M", "H"].iter().position(|r| *r == p);
+        return rank(lhs).cmp(&rank(rhs));
+    }
+    match (lhs.parse::<f64>(), rhs.parse::<f64>()) {
+        (Ok(lhs), Ok(rhs)) => lhs.total_cmp(&rhs),
+        _ => lhs.cmp(rhs),
+    }
+}
+
+fn date_matches(
+    task_date: Option<DateTime<Utc>>,
+    modifier: Modifier,
+    date: Option<(DateTime<Utc>, bool)>,
+) -> bool {
+    let is = |task_date: Option<DateTime<Utc>>| match (task_date, date) {
+        (Some(task_date), Some((date, true))) => task_date.date_naive() == date.date_naive(),
+        (Some(task_date), Some((date, false))) => task_date == date,
+        _ => false,
+    };
+    match modifier {
+        Modifier::Before => task_date.zip(date).is_some_and(|(p, (d, _))| p < d),
+        Modifier::After => task_date.zip(date).is_some_and(|(p, (d, _))| p > d),
+        Modifier::Is => is(task_date),
+        Modifier::Not => !is(task_date),
+        Modifier::Any => task_date.is_some(),
+        // other modifiers are rejected while parsing
+        _ => task_date.is_none(),
+    }
+}
+
+fn modified_matches(task: &Task, attribute: &str, modifier: Modifier, value: &str) -> bool {
+    let is = |task: &Task| {
+        if attribute == "status" {
+            attribute_matches(task, attribute, value)
+        } else {
+            attribute_value(task, attribute) == value
+        }
+    };
+    let task_value = attribute_value(task, attribute);
+    match modifier {
+        Modifier::Before => {
+            !task_value.is_empty() && compare_values(attribute, &task_value, value).is_lt()
+        }
+        Modifier::After => {
+            !task_value.is_empty() && compare_values(attribute, &task_value, value).is_gt()
+        }
+        Modifier::Is => is(task),
+        Modifier::Not => !is(task),
+        Modifier::Has => task_value.contains(value),
+        Modifier::Hasnt => !task_value.contains(value),
+        Modifier::StartsWith => task_value.starts_with(value),
+        Modifier::EndsWith => task_value.ends_with(value),
+        Modifier::Any => !task_value.is_empty(),
+        Modifier::None => task_value.is_empty(),
+    }
+}
+
+fn term_matches(term: &FilterTerm, task: &Task, now: DateTime<Utc>) -> bool {
+    match term {
+        FilterTerm::HasTag(tag) => has_tag(task, tag, now),
+        FilterTerm::HasNotTag(tag) => !has_tag(task, tag, now),
+        FilterTerm::Attribute(attribute, value) => attribute_matches(task, attribute, value),
+        FilterTerm::Date {
+            attribute,
+            date,
+            whole_day,
+        } => task_date(task, attribute).is_some_and(|p| {
+            if *whole_day {
+                p.date_naive() == date.date_naive()
+            } else {
+                p == *date
+            }
+        }),
+        FilterTerm::Modified {
+            attribute,
+            modifier,
+            value,
+            date,
+        } => {
+            if DATE_ATTRIBUTES.contains(&attribute.as_str()) {
+                date_matches(task_date(task, attribute), *modifier, *date)
+            } else {
+                modified_matches(task, attribute, *modifier, value)
+            }
+        }
+        FilterTerm::Ids(ids) => task.id.is_some_and(|p| ids.iter().any(|r| r.contains(&p))),
+        FilterTerm::Uuid(uuid) => task.uuid.to_string().starts_with(uuid.as_str()),
+        FilterTerm::Pattern(pattern) => {
+            task.description.contains(pattern.as_str())
+                || task
+                    .annotations
+                    .as_ref()
+                    .is_some_and(|p| p.iter().any(|a| a.description().contains(pattern.as_str())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
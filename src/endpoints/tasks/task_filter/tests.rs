@@ -0,0 +1,177 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+use super::*;
+use crate::TWGlobalState;
+use chrono::TimeZone;
+use taskchampion::Uuid;
+
+fn sample_task() -> Task {
+    Task {
+        id: Some(3),
+        description: "Buy groceries for the weekend".to_string(),
+        project: Some("Home.Kitchen".to_string()),
+        uuid: Uuid::parse_str("ec2c596f-5fa3-442c-80ee-98b087e32bbd").unwrap(),
+        status: Some(Status::Pending),
+        tags: Some(vec!["shopping".to_string()]),
+        priority: Some("H".to_string()),
+        due: Some(Utc.with_ymd_and_hms(2025, 5, 2, 14, 0, 0).unwrap()),
+        synthetic_tags: vec!["PENDING".to_string(), "UNBLOCKED".to_string()],
+        ..Task::default()
+    }
+}
+
+fn assert_filter(filter: &str, expected: bool) {
+    let now = Utc.with_ymd_and_hms(2025, 5, 1, 8, 0, 0).unwrap();
+    let task_filter = TaskFilter::parse(filter).unwrap();
+    assert_eq!(
+        task_filter.matches(&sample_task(), now),
+        expected,
+        "filter {filter}"
+    );
+}
+
+#[test]
+fn parsing_filter_terms() {
+    let task_filter = TaskFilter::parse("+home -work pro:Home 1,4-5 ec2c596f groceries").unwrap();
+    assert_eq!(
+        task_filter.expressions(),
+        &vec![
+            FilterExpr::Term(FilterTerm::HasTag("home".into())),
+            FilterExpr::Term(FilterTerm::HasNotTag("work".into())),
+            FilterExpr::Term(FilterTerm::Attribute("project".into(), "Home".into())),
+            FilterExpr::Term(FilterTerm::Ids(vec![1..=1, 4..=5])),
+            FilterExpr::Term(FilterTerm::Uuid("ec2c596f".into())),
+            FilterExpr::Term(FilterTerm::Pattern("groceries".into())),
+        ]
+    );
+    assert_eq!(TaskFilter::parse("limit:5").unwrap().limit(), Some(5));
+    // huge ranges are not expanded.
+    assert_eq!(
+        TaskFilter::parse("1-9999999999").unwrap().expressions(),
+        &vec![FilterExpr::Term(FilterTerm::Ids(vec![1..=9_999_999_999]))]
+    );
+    assert_filter("1-9999999999", true);
+    assert_filter("4-9999999999", false);
+    assert!(TaskFilter::parse("due:someday").is_err());
+}
+
+#[test]
+fn matching_attributes_and_tags() {
+    assert_filter("+shopping", true);
+    assert_filter("-shopping", false);
+    assert_filter("+PENDING +UNBLOCKED", true);
+    assert_filter("+ACTIVE", false);
+    assert_filter("+DUE +PROJECT", true);
+    assert_filter("project:Home", true);
+    assert_filter("project:Kitchen", false);
+    assert_filter("priority:H status:pending", true);
+    assert_filter("priority:", false);
+    assert_filter("due:2025-05-02", true);
+    assert_filter("due:2025-05-03", false);
+    assert_filter("scheduled:", true);
+    assert_filter("3 groceries", true);
+    assert_filter("4", false);
+    assert_filter("ec2c596f", true);
+    assert_filter("customer:", true);
+    assert_filter("customer:ACME", false);
+}
+
+#[test]
+fn filter_from_task_query() {
+    let task_query = TaskQuery::new(TWGlobalState {
+        query: Some("project:Home".to_string()),
+        ..TWGlobalState::default()
+    });
+    let task_filter = TaskFilter::from_query(&task_query).unwrap();
+    assert_eq!(
+        task_filter.expressions(),
+        &vec![
+            FilterExpr::Term(FilterTerm::Attribute("status".into(), "pending".into())),
+            FilterExpr::Term(FilterTerm::HasNotTag("WAITING".into())),
+            FilterExpr::Term(FilterTerm::Attribute("project".into(), "Home".into())),
+        ]
+    );
+    let now = Utc.with_ymd_and_hms(2025, 5, 1, 8, 0, 0).unwrap();
+    assert!(task_filter.matches(&sample_task(), now));
+}
+
+#[test]
+fn parsing_filter_expressions() {
+    let task_filter = TaskFilter::parse("+home or (project:Work and +urgent) xor +later").unwrap();
+    assert_eq!(
+        task_filter.expressions(),
+        &vec![FilterExpr::Or(vec![
+            FilterExpr::Term(FilterTerm::HasTag("home".into())),
+            FilterExpr::Xor(
+                Box::new(FilterExpr::And(vec![
+                    FilterExpr::Term(FilterTerm::Attribute("project".into(), "Work".into())),
+                    FilterExpr::Term(FilterTerm::HasTag("urgent".into())),
+                ])),
+                Box::new(FilterExpr::Term(FilterTerm::HasTag("later".into()))),
+            ),
+        ])]
+    );
+    assert_eq!(
+        TaskFilter::parse("pro.not:Home").unwrap().expressions(),
+        &vec![FilterExpr::Term(FilterTerm::Modified {
+            attribute: "project".into(),
+            modifier: Modifier::Not,
+            value: "Home".into(),
+            date: None,
+        })]
+    );
+    assert!(TaskFilter::parse("(+home or +work").is_err());
+    assert!(TaskFilter::parse("+home or +work)").is_err());
+    assert!(TaskFilter::parse("+home or").is_err());
+    assert!(TaskFilter::parse("and +home").is_err());
+    assert!(TaskFilter::parse("()").is_err());
+    assert!(TaskFilter::parse("due.sometime:2025-05-01").is_err());
+    assert!(TaskFilter::parse("due.has:2025").is_err());
+}
+
+#[test]
+fn matching_filter_expressions() {
+    assert_filter("+work or +shopping", true);
+    assert_filter("+work or -shopping", false);
+    assert_filter("(+work or +shopping) and project:Home", true);
+    assert_filter("+shopping xor project:Home", false);
+    assert_filter("+shopping xor project:Work", true);
+    assert_filter("+work or +home +shopping", false);
+    assert_filter("(+work or +home) +shopping", false);
+    assert_filter("+shopping (project:Work or priority:H)", true);
+}
+
+#[test]
+fn matching_attribute_modifiers() {
+    assert_filter("due.before:2025-05-03", true);
+    assert_filter("due.after:2025-05-03", false);
+    assert_filter("due.is:2025-05-02", true);
+    assert_filter("due.not:2025-05-02", false);
+    assert_filter("due.any:", true);
+    assert_filter("scheduled.none:", true);
+    assert_filter("scheduled.before:2025-05-03", false);
+    assert_filter("project.is:Home", false);
+    assert_filter("project.is:Home.Kitchen", true);
+    assert_filter("project.not:Home", true);
+    assert_filter("project.startswith:Home", true);
+    assert_filter("project.endswith:Kitchen", true);
+    assert_filter("description.has:groceries", true);
+    assert_filter("description.hasnt:groceries", false);
+    assert_filter("tags.has:shopping", true);
+    assert_filter("tags.hasnt:shopping", false);
+    assert_filter("tags.none:", false);
+    assert_filter("priority.above:M", true);
+    assert_filter("priority.below:H", false);
+    assert_filter("status.not:completed", true);
+    assert_filter("customer.none:", true);
+    assert_filter("customer.any:", false);
+    assert_filter("id.below:5", true);
+    assert_filter("urgency.over:5", false);
+}
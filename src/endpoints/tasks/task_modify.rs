@@ -11,9 +11,12 @@ use std::str::FromStr;
 
 use crate::{
     backend::task::convert_task_status,
-    core::errors::{FieldError, FormValidation},
+    core::{
+        date_expr::parse_date_expr,
+        errors::{FieldError, FormValidation},
+    },
 };
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::Utc;
 use taskchampion::storage::Storage;
 use taskchampion::{Replica, Tag, Uuid};
 
@@ -191,36 +194,30 @@ pub fn task_apply_timestamps(
     b1: (String, Option<String>),
 ) {
     let dt = match b1.1 {
-        Some(val) if !val.trim().is_empty() => val
-            .trim()
-            .parse::<DateTime<Utc>>()
-            .or_else(|_| {
-                val.parse::<NaiveDate>().map(|p| {
-                    p.and_time(
-                        NaiveTime::from_num_seconds_from_midnight_opt(0, 0)
-                            .expect("Failed even to create the simplest Time object"),
-                    )
-                    .and_utc()
-                })
-            })
+        Some(val) if !val.trim().is_empty() => parse_date_expr(&val, Utc::now())
+            .map(|(date, _)| date)
             .map_err(|p| FieldError {
                 field: "additional".into(),
-                message: format!("Failed parsing timestamp for {} ({}).", &b1.0, p),
+                message: format!("Failed parsing timestamp for {} ({}).", b1.0, p),
             })
             .map(Some),
         Some(_) | None => Ok(None),
     };
     match dt {
         Ok(e) => {
-            let result = match b1.0.to_lowercase().trim() {
+            let property = b1.0.to_lowercase();
+            let result = match property.trim() {
                 "entry" => t.set_entry(e, ops),
                 "wait" => t.set_wait(e, ops),
                 "due" => t.set_due(e, ops),
+                "scheduled" | "until" => t.set_timestamp(property.trim(), e, ops),
+                // checked by `task_validate_end` once the status is known.
+                "end" => t.set_timestamp("end", e, ops),
                 _ => Ok(()),
             }
             .map_err(|p| FieldError {
                 field: "additional".into(),
-                message: format!("Failed setting timestamp for {} ({}).", &b1.0, p),
+                message: format!("Failed setting timestamp for {} ({}).", b1.0, p),
             });
             if let Err(p) = result {
                 validation_result.push(p);
@@ -230,6 +227,20 @@ pub fn task_apply_timestamps(
     }
 }
 
+/// Checks the `end` date given with the command, once all fragments
+/// were applied, as the status may be changed after it.
+pub fn task_validate_end(t: &taskchampion::Task, validation_result: &mut FormValidation) {
+    if !matches!(
+        t.get_status(),
+        taskchampion::Status::Completed | taskchampion::Status::Deleted
+    ) {
+        validation_result.push(FieldError {
+            field: "additional".into(),
+            message: String::from("An end date can only be set on completed or deleted tasks."),
+        });
+    }
+}
+
 pub fn task_apply_status(
     t: &mut taskchampion::Task,
     ops: &mut Vec<taskchampion::Operation>,
@@ -240,7 +251,7 @@ pub fn task_apply_status(
         let task_status = convert_task_status(&val);
         match t.set_status(task_status, ops).map_err(|p| FieldError {
             field: "additional".into(),
-            message: format!("Invalid status {} ({}).", &val, p),
+            message: format!("Invalid status {} ({}).", val, p),
         }) {
             Ok(()) => (),
             Err(p) => validation_result.push(p),
@@ -9,8 +9,9 @@
  */
 
 use crate::TWGlobalState;
+use crate::backend::task::Task;
 use serde::{Deserialize, Serialize};
-use std::cmp::PartialEq;
+use std::cmp::{PartialEq, Reverse};
 use std::fmt::{Display, Formatter};
 use std::process::Command;
 use tracing::log::trace;
@@ -58,6 +59,41 @@ impl From<String> for TaskReport {
     }
 }
 
+impl TaskReport {
+    /// Default filter of the report, same as taskwarrior's `report.<name>.filter`.
+    pub const fn filter(&self) -> &'static str {
+        match self {
+            Self::Next | Self::New => "status:pending -WAITING",
+            Self::Ready => "+READY",
+            Self::All | Self::NotSet => "",
+        }
+    }
+
+    /// Sorts the tasks like taskwarrior's `report.<name>.sort`.
+    ///
+    /// - next, ready: `start-,urgency-`
+    /// - new, all: `entry-`
+    ///
+    /// Without a report, tasks are ordered by id and entry.
+    pub fn sort(&self, tasks: &mut [Task]) {
+        let by_urgency = |lhs: &Task, rhs: &Task| {
+            rhs.urgency
+                .unwrap_or_default()
+                .total_cmp(&lhs.urgency.unwrap_or_default())
+        };
+        match self {
+            Self::Next | Self::Ready => tasks.sort_by(|lhs, rhs| {
+                rhs.start
+                    .cmp(&lhs.start)
+                    .then_with(|| by_urgency(lhs, rhs))
+                    .then(lhs.id.cmp(&rhs.id))
+            }),
+            Self::New | Self::All => tasks.sort_by_key(|task| Reverse(task.entry)),
+            Self::NotSet => tasks.sort_by_key(|task| (task.id.is_none(), task.id, task.entry)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub enum TaskPriority {
     High,
@@ -154,6 +190,52 @@ impl Default for TaskQuery {
     }
 }
 
+/// Query parameters of the JSON API, without the state the html views carry along.
+///
+/// `project` is given without the `project:` prefix, `tags` like `+home -work`.
+/// Without a `report` or `status`, the `next` report is used.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TaskQueryParams {
+    /// `next`, `new`, `ready` or `all`.
+    pub report: Option<String>,
+    /// `pending`, `completed` or `waiting`.
+    pub status: Option<String>,
+    /// `H`, `M` or `L`.
+    pub priority: Option<String>,
+    pub project: Option<String>,
+    /// Tags like `+home -work`.
+    pub tags: Option<String>,
+    /// Filter like `due.before:eow or +next`.
+    pub filter: Option<String>,
+}
+
+impl From<TaskQueryParams> for TaskQuery {
+    fn from(params: TaskQueryParams) -> Self {
+        let mut tq = Self::default();
+        if let Some(status) = params.status {
+            tq.status = status.into();
+            tq.report = TaskReport::NotSet;
+        }
+        if let Some(report) = params.report {
+            tq.report = report.into();
+        }
+        if let Some(priority) = params.priority {
+            tq.priority = priority.into();
+        }
+        tq.project = params
+            .project
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("project:{}", p.trim_start_matches("project:")));
+        tq.tags = params
+            .tags
+            .map(|p| p.split_whitespace().map(String::from).collect())
+            .unwrap_or_default();
+        tq.filter = params.filter.filter(|p| !p.trim().is_empty());
+        tq
+    }
+}
+
 impl TaskQuery {
     pub fn new(params: TWGlobalState) -> Self {
         let mut tq = Self::default();
@@ -117,3 +117,35 @@ fn error_with_default_queryset() {
     }"#;
     parse_text_and_assert_query(query_text, &["export", "ready"]);
 }
+
+#[test]
+fn task_query_from_api_params() {
+    let task_query = TaskQuery::from(TaskQueryParams::default());
+    assert_eq!(task_query.get_query(true), &["export", "next"]);
+
+    let task_query = TaskQuery::from(TaskQueryParams {
+        status: Some("completed".to_string()),
+        priority: Some("H".to_string()),
+        project: Some("Home.Garden".to_string()),
+        tags: Some("+garden -work".to_string()),
+        filter: Some("due.before:eow".to_string()),
+        ..TaskQueryParams::default()
+    });
+    assert_eq!(
+        task_query.get_query(false),
+        &[
+            "due.before:eow",
+            "priority:H",
+            "project:Home.Garden",
+            "+garden",
+            "-work",
+            "status:completed"
+        ]
+    );
+
+    let task_query = TaskQuery::from(TaskQueryParams {
+        report: Some("all".to_string()),
+        ..TaskQueryParams::default()
+    });
+    assert_eq!(task_query.get_query(true), &["export", "all"]);
+}
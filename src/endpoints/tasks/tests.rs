@@ -10,11 +10,21 @@
 
 use std::str::FromStr;
 
-use crate::{NewTask, backend::task::get_replica, endpoints::tasks::run_modify_command};
-use chrono::{Datelike, Days, Months, Timelike, Utc};
+use crate::{
+    NewTask,
+    backend::task::{Annotation, edit_annotation, get_replica, get_undo_history},
+    endpoints::tasks::{run_annotate_command, run_denotate_command, run_modify_command},
+};
+use chrono::{Datelike, Days, Months, TimeZone, Timelike, Utc};
 use taskchampion::{Status, Tag, Uuid};
 
-use super::task_add;
+use super::{
+    api_denotate_task_entry, api_edit_task_annotation, fetch_active_task, get_task_details,
+    list_tasks, task_add, task_details_context, task_undo, toggle_task_active,
+};
+use crate::TWGlobalState;
+use crate::core::errors::FormValidation;
+use crate::endpoints::tasks::task_query_builder::TaskQuery;
 
 #[tokio::test]
 async fn test_task_add() -> anyhow::Result<()> {
@@ -27,6 +37,8 @@ async fn test_task_add() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -60,6 +72,8 @@ async fn test_task_add_fail() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H due:\"".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_err());
@@ -82,6 +96,8 @@ async fn test_task_modify_successful() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -102,6 +118,8 @@ async fn test_task_modify_successful() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -186,6 +204,8 @@ async fn test_task_modify_breakit() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -250,6 +270,8 @@ async fn test_task_add_recur() -> anyhow::Result<()> {
             "priority:H recur:monthly due:{}",
             dt_wait.format("%Y-%m-%d")
         )),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -280,6 +302,8 @@ async fn test_task_append_dependency() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some("priority:H".into()),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -301,6 +325,8 @@ async fn test_task_append_dependency() -> anyhow::Result<()> {
         project: Some("TWK".into()),
         filter_value: None,
         additional: Some(format!("depends:{}", our_task.0)),
+        scheduled: None,
+        until: None,
     };
     let result = task_add(&task, &app_state).await;
     assert!(result.is_ok());
@@ -312,3 +338,448 @@ async fn test_task_append_dependency() -> anyhow::Result<()> {
     let _ = tmp_dir.close();
     Ok(())
 }
+
+#[tokio::test]
+async fn test_list_tasks_native() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    for (description, project, tags) in [
+        ("Repair the fence", "Home.Garden", "+outdoor"),
+        ("Plan the sprint", "Work", "+office"),
+        ("Water the plants", "Home", "+outdoor"),
+    ] {
+        let task = NewTask {
+            description: description.to_string(),
+            tags: Some(tags.into()),
+            project: Some(project.into()),
+            filter_value: None,
+            additional: None,
+            scheduled: None,
+            until: None,
+        };
+        task_add(&task, &app_state).await?;
+    }
+
+    let tasks = list_tasks(&TaskQuery::default(), &app_state).await?;
+    assert_eq!(tasks.len(), 3);
+
+    let task_query = TaskQuery::new(TWGlobalState {
+        query: Some("project:Home".to_string()),
+        ..TWGlobalState::default()
+    });
+    let tasks = list_tasks(&task_query, &app_state).await?;
+    assert_eq!(tasks.len(), 2);
+    assert!(
+        tasks
+            .values()
+            .all(|p| p.project.as_ref().is_some_and(|p| p.starts_with("Home")))
+    );
+
+    let mut task_query = TaskQuery::default();
+    task_query.set_filter("+office sprint");
+    let tasks = list_tasks(&task_query, &app_state).await?;
+    assert_eq!(tasks.len(), 1);
+    let sprint_task = tasks.values().next().unwrap().uuid;
+
+    assert!(fetch_active_task(&app_state).await?.is_none());
+    toggle_task_active(sprint_task, "start".to_string(), &app_state).await?;
+    let active_task = fetch_active_task(&app_state).await?;
+    assert_eq!(active_task.map(|p| p.uuid), Some(sprint_task));
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_builtin_reports_sorted_by_urgency() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    for (description, additional) in [
+        ("Low priority", "priority:L"),
+        ("Next action", "+next"),
+        ("Nothing special", ""),
+    ] {
+        let task = NewTask {
+            description: description.to_string(),
+            tags: None,
+            project: None,
+            filter_value: None,
+            additional: Some(additional.into()),
+            scheduled: None,
+            until: None,
+        };
+        task_add(&task, &app_state).await?;
+    }
+
+    let tasks = list_tasks(&TaskQuery::default(), &app_state).await?;
+    let descriptions: Vec<&str> = tasks.values().map(|p| p.description.as_str()).collect();
+    assert_eq!(
+        descriptions,
+        vec!["Next action", "Low priority", "Nothing special"]
+    );
+    assert!(tasks.values().all(|p| p.urgency.is_some()));
+
+    let nothing_special = tasks.values().last().unwrap().uuid;
+    toggle_task_active(nothing_special, "start".to_string(), &app_state).await?;
+    let task_query = TaskQuery::new(TWGlobalState {
+        report: Some("ready".to_string()),
+        ..TWGlobalState::default()
+    });
+    let tasks = list_tasks(&task_query, &app_state).await?;
+    assert_eq!(tasks.values().next().map(|p| p.uuid), Some(nothing_special));
+
+    // the active task outranks the more urgent +next task in the next report too
+    let tasks = list_tasks(&TaskQuery::default(), &app_state).await?;
+    let descriptions: Vec<&str> = tasks.values().map(|p| p.description.as_str()).collect();
+    assert_eq!(
+        descriptions,
+        vec!["Nothing special", "Next action", "Low priority"]
+    );
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_tasks_invalid_filter() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let mut task_query = TaskQuery::default();
+    task_query.set_filter("(+home or +office");
+    let err = list_tasks(&task_query, &app_state).await.unwrap_err();
+    let validation = err.downcast::<FormValidation>()?;
+    assert!(validation.has_error("filter"));
+    assert!(!validation.is_success());
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_add_named_dates() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Call the plumber".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: Some("due:tomorrow wait:+3d".into()),
+        scheduled: None,
+        until: None,
+    };
+    let before = Utc::now();
+    task_add(&task, &app_state).await?;
+
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let tasks = replica.all_tasks().await?;
+    let task = tasks.values().next().unwrap();
+    let tomorrow = before
+        .date_naive()
+        .succ_opt()
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    assert_eq!(task.get_due(), Some(tomorrow));
+    let wait = task.get_wait().unwrap();
+    // timestamps are stored with second precision
+    assert!(wait >= before + chrono::Duration::days(3) - chrono::Duration::seconds(1));
+    assert!(wait <= Utc::now() + chrono::Duration::days(3));
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_add_scheduled_until() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Renew the passport".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: Some(String::new()),
+        scheduled: Some("2025-06-01".into()),
+        until: Some("2025-07-01T12:00:00Z".into()),
+    };
+    let uuid = task_add(&task, &app_state).await?;
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(
+        task.get_timestamp("scheduled"),
+        Some(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        task.get_timestamp("until"),
+        Some(Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap())
+    );
+
+    let task = NewTask {
+        description: "Renew the passport".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: None,
+        scheduled: Some("someday".into()),
+        until: Some(" ".into()),
+    };
+    let validation = task_add(&task, &app_state).await.unwrap_err();
+    assert!(validation.has_error("scheduled"));
+    assert!(!validation.has_error("until"));
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_modify_scheduled_until_end() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Renew the passport".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: Some("scheduled:2025-06-01 until:2025-07-01T12:00:00Z".into()),
+        scheduled: None,
+        until: None,
+    };
+    let uuid = task_add(&task, &app_state).await.unwrap();
+
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    let scheduled = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap();
+    assert_eq!(task.get_timestamp("scheduled"), Some(scheduled));
+    assert_eq!(task.get_timestamp("until"), Some(until));
+
+    let result = run_modify_command(uuid, "scheduled:someday end:2025-06-02", &app_state)
+        .await
+        .unwrap_err();
+    let errors = result.fields.get("additional").unwrap();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].message.contains("scheduled"));
+    assert!(errors[1].message.contains("end date"));
+
+    // the status is checked once all fragments were applied.
+    let end = Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap();
+    run_modify_command(uuid, "end:2025-06-02 status:completed until:", &app_state)
+        .await
+        .unwrap();
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_timestamp("end"), Some(end));
+    assert_eq!(task.get_timestamp("until"), None);
+
+    let result = run_modify_command(uuid, "end:2025-06-03 status:pending", &app_state)
+        .await
+        .unwrap_err();
+    let errors = result.fields.get("additional").unwrap();
+    assert!(errors[0].message.contains("end date"));
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_timestamp("end"), Some(end));
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_undo_multiple_steps() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Water the plants".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: None,
+        scheduled: None,
+        until: None,
+    };
+    let uuid = task_add(&task, &app_state).await.unwrap();
+    run_modify_command(uuid, "project:Home", &app_state)
+        .await
+        .unwrap();
+    run_modify_command(uuid, "priority:H", &app_state)
+        .await
+        .unwrap();
+
+    let history = get_undo_history(&app_state.replica).await?;
+    assert_eq!(history.len(), 3);
+    assert!(
+        history[0]
+            .operations
+            .iter()
+            .any(|p| p.property.as_deref() == Some("priority"))
+    );
+    assert!(
+        history[1]
+            .operations
+            .iter()
+            .any(|p| p.property.as_deref() == Some("project"))
+    );
+    assert_eq!(history[2].operations[0].operation, "Create");
+
+    assert_eq!(task_undo(2, &app_state).await?, 2);
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_description(), "Water the plants");
+    assert_eq!(task.get_value("project"), None);
+    assert_eq!(task.get_priority(), "");
+    assert_eq!(get_undo_history(&app_state.replica).await?.len(), 1);
+
+    assert_eq!(task_undo(5, &app_state).await?, 1);
+    assert!(replica.get_task(uuid).await?.is_none());
+    assert_eq!(task_undo(1, &app_state).await?, 0);
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_annotate_denotate() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Call the plumber".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: None,
+        scheduled: None,
+        until: None,
+    };
+    let uuid = task_add(&task, &app_state).await.unwrap();
+
+    let result = run_annotate_command(uuid, "  ", &app_state)
+        .await
+        .unwrap_err();
+    assert!(result.has_error("annotation"));
+    let result = run_denotate_command(uuid, &app_state).await.unwrap_err();
+    assert!(result.has_error("annotation"));
+
+    run_annotate_command(uuid, "Asked for \"Bob's\"  number", &app_state)
+        .await
+        .unwrap();
+    run_annotate_command(uuid, "Left a message", &app_state)
+        .await
+        .unwrap();
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    let mut annotations: Vec<_> = task.get_annotations().collect();
+    annotations.sort_by_key(|p| p.entry);
+    assert_eq!(annotations.len(), 2);
+    assert_eq!(annotations[0].description, "Asked for \"Bob's\"  number");
+    assert_eq!(annotations[1].description, "Left a message");
+
+    run_denotate_command(uuid, &app_state).await.unwrap();
+    let task = replica.get_task(uuid).await?.unwrap();
+    let annotations: Vec<_> = task.get_annotations().collect();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].description, "Asked for \"Bob's\"  number");
+
+    let result = run_annotate_command(Uuid::new_v4(), "Missing", &app_state)
+        .await
+        .unwrap_err();
+    assert!(!result.is_success());
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_edit_annotation() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask {
+        description: "Prepare the talk".to_string(),
+        tags: None,
+        project: None,
+        filter_value: None,
+        additional: None,
+        scheduled: None,
+        until: None,
+    };
+    let uuid = task_add(&task, &app_state).await.unwrap();
+    run_annotate_command(uuid, "Slides are in the shared folder", &app_state)
+        .await
+        .unwrap();
+    let mut replica = get_replica(&app_state.task_storage_path).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    let entry = task.get_annotations().next().unwrap().entry;
+
+    let annotation = Annotation::new(entry, "Slides are in the talks folder");
+    edit_annotation(uuid, &annotation, &app_state).await?;
+    let task = replica.get_task(uuid).await?.unwrap();
+    let annotations: Vec<_> = task.get_annotations().collect();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].entry, entry);
+    assert_eq!(annotations[0].description, "Slides are in the talks folder");
+
+    let history = get_undo_history(&app_state.replica).await?;
+    assert_eq!(history.len(), 3);
+    let annotation_key = format!("annotation_{}", entry.timestamp());
+    assert!(
+        history[0]
+            .operations
+            .iter()
+            .any(|p| p.property.as_ref() == Some(&annotation_key))
+    );
+
+    let missing = Annotation::new(entry + chrono::Duration::days(1), "Unknown");
+    assert!(edit_annotation(uuid, &missing, &app_state).await.is_err());
+
+    let response = api_edit_task_annotation(
+        axum::extract::Path(uuid),
+        axum::extract::State(app_state.clone()),
+        axum::Form(Annotation::new(entry, "Rehearse on Friday")),
+    )
+    .await;
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(response.body().contains("Rehearse on Friday"));
+    let task = replica.get_task(uuid).await?.unwrap();
+    let annotations: Vec<_> = task.get_annotations().collect();
+    assert_eq!(annotations[0].description, "Rehearse on Friday");
+
+    let response = api_edit_task_annotation(
+        axum::extract::Path(uuid),
+        axum::extract::State(app_state.clone()),
+        axum::Form(Annotation::new(entry, " ")),
+    )
+    .await;
+    assert!(
+        response
+            .body()
+            .contains("An annotation requires a description.")
+    );
+
+    let response = api_denotate_task_entry(
+        axum::extract::Path(uuid),
+        axum::extract::State(app_state.clone()),
+        axum::Form(missing),
+    )
+    .await;
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    assert!(
+        response
+            .body()
+            .contains("The annotation does not exist anymore")
+    );
+    let task = replica.get_task(uuid).await?.unwrap();
+    assert_eq!(task.get_annotations().count(), 1);
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_details_context() -> anyhow::Result<()> {
+    let (tmp_dir, app_state) = crate::get_random_appstate();
+    let task = NewTask::new(Some("Prepare the talk".into()), None, None, None, None);
+    let uuid = task_add(&task, &app_state).await.unwrap();
+    run_annotate_command(uuid, "Slides are in the shared folder", &app_state)
+        .await
+        .unwrap();
+
+    let mut task = get_task_details(uuid, &app_state).await?;
+    let ctx = task_details_context(&mut task, &app_state).await;
+    let shortcuts = ctx.get("annotate_shortcuts").unwrap().as_array().unwrap();
+    assert_eq!(shortcuts.len(), 1);
+    assert!(ctx.get("urgency_terms").is_some());
+    assert!(ctx.get("task").is_some());
+
+    let _ = tmp_dir.close();
+    Ok(())
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+use std::sync::Arc;
+
+use reqwest::{Method, StatusCode};
+use taskchampion::Uuid;
+use tokio::net::TcpListener;
+
+use super::*;
+use crate::backend::auth::hash_password;
+use crate::core::config::{AppSettings, AuthSettings, AuthUser};
+
+/// Routes of the web UI, the API routes are taken from its OpenAPI document.
+const UI_ROUTES: &[&str] = &[
+    "/",
+    "/tasks",
+    "/tasks/undo/report",
+    "/tasks/undo/confirmed",
+    "/tasks/add",
+    "/tasks/active",
+    "/tasks/{id}/details",
+    "/tasks/{id}/delete",
+    "/tasks/{id}/denotate",
+    "/tasks/{id}/annotation",
+    "/msg",
+    "/msg_clr",
+    "/tag_bar",
+    "/task_action_bar",
+    "/bars",
+    "/sync",
+    "/events",
+    "/hooks/log",
+    "/settings/tokens",
+    "/settings/tokens/{id}",
+    api::OPENAPI_PATH,
+];
+
+/// Serves the [`app`] on a random port and returns its url, shared by the endpoint tests.
+pub(crate) async fn serve(app_state: AppState) -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let app = app(app_state);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok(url)
+}
+
+#[tokio::test]
+async fn test_all_routes_require_login() -> anyhow::Result<()> {
+    let (_tmp_dir, mut app_state) = crate::get_random_appstate();
+    app_state.app_config = Arc::new(AppSettings {
+        auth: AuthSettings {
+            users: vec![AuthUser {
+                username: String::from("alex"),
+                password_hash: hash_password("correct horse")?,
+            }],
+            ..AuthSettings::default()
+        },
+        ..AppSettings::default()
+    });
+    let url = serve(app_state).await?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    let openapi = api::openapi();
+    let routes = UI_ROUTES
+        .iter()
+        .map(ToString::to_string)
+        .chain(openapi.paths.paths.keys().cloned());
+    let uuid = Uuid::new_v4().to_string();
+    for route in routes {
+        let path = route.replace("{id}", &uuid);
+        let mut served = false;
+        for method in [
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ] {
+            let response = client
+                .request(method.clone(), format!("{url}{path}"))
+                .send()
+                .await?;
+            let status = response.status();
+            // the login is checked before anything else of a route.
+            if status == StatusCode::METHOD_NOT_ALLOWED {
+                continue;
+            }
+            served = true;
+            assert!(
+                status == StatusCode::UNAUTHORIZED || status == StatusCode::SEE_OTHER,
+                "{method} {route} answered {status} without a login"
+            );
+        }
+        assert!(served, "{route} is not served");
+    }
+    Ok(())
+}
@@ -0,0 +1,686 @@
+/*
+ * Copyright 2025 Tarin Mahmood
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of this software and associated documentation files (the “Software”), to deal in the Software without restriction, including without limitation the rights to use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! The pages of the web UI, rendered from the templates.
+
+use std::collections::{HashMap, HashSet};
+use std::string::ToString;
+
+use axum::Form;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, Response};
+use indexmap::IndexMap;
+use taskchampion::Uuid;
+use tracing::{error, info, trace};
+
+use crate::backend::hooks::track_hook_failures;
+use crate::backend::task::{UndoGroup, get_project_list, get_undo_history};
+use crate::core::app::{AppState, get_default_context};
+use crate::core::cache::MnemonicsType;
+use crate::core::config::CustomQuery;
+use crate::core::errors::{AppError, FormValidation, is_database_busy};
+use crate::core::utils::{make_shortcut, make_shortcut_cache};
+use crate::endpoints::hooks::HOOK_LOG_PATH;
+use crate::endpoints::tasks::task_query_builder::TaskQuery;
+use crate::endpoints::tasks::{self, change_task_status};
+use crate::endpoints::tasks::{
+    TaskUUID, TaskViewDataRetType, fetch_active_task, list_tasks, run_annotate_command,
+    run_denotate_command, run_modify_command, task_add, task_undo, toggle_task_active,
+};
+use crate::endpoints::tasks::{get_task_details, task_details_context};
+use crate::{
+    DIST_CONTENT, FlashMsg, FlashMsgRoles, NewTask, TEMPLATES, TWGlobalState, TaskActions,
+    task_query_merge_previous_params, task_query_previous_params,
+};
+
+pub(crate) async fn get_active_task(app_state: State<AppState>) -> Html<String> {
+    let mut ctx = get_default_context(&app_state);
+    if let Ok(Some(v)) = fetch_active_task(&app_state).await {
+        ctx.insert("active_task", &v);
+    }
+    Html(TEMPLATES.render("active_task.html", &ctx).unwrap())
+}
+
+pub(crate) async fn get_task_action_bar(app_state: State<AppState>) -> Html<String> {
+    let ctx = get_default_context(&app_state);
+    Html(TEMPLATES.render("task_action_bar.html", &ctx).unwrap())
+}
+
+pub(crate) async fn check_and_sync(app_state: State<AppState>) -> Html<String> {
+    let Some(sync_settings) = app_state.app_config.sync.as_ref() else {
+        return Html(String::new());
+    };
+    let fm = match app_state
+        .sync_state
+        .sync(&app_state.replica, sync_settings)
+        .await
+    {
+        Ok(report) => FlashMsg::new(
+            &format!(
+                "Synchronized tasks, pulled {} and pushed {} versions",
+                report.pulled, report.pushed
+            ),
+            None,
+            FlashMsgRoles::Success,
+        ),
+        Err(e) => {
+            error!("Failed to synchronize: {:?}", e);
+            FlashMsg::new(
+                &format!("Synchronization failed: {e}"),
+                None,
+                FlashMsgRoles::Error,
+            )
+        }
+    };
+    let mut ctx = get_default_context(&app_state);
+    fm.to_context(&mut ctx);
+    Html(TEMPLATES.render("flash_msg.html", &ctx).unwrap())
+}
+
+pub(crate) async fn get_bar(
+    Query(param): Query<HashMap<String, String>>,
+    app_state: State<AppState>,
+) -> Html<String> {
+    param.get("bar").map_or_else(
+        || Html(String::new()),
+        |bar| {
+            let ctx = get_default_context(&app_state);
+            if bar == "left_action_bar" {
+                Html(TEMPLATES.render("left_action_bar.html", &ctx).unwrap())
+            } else {
+                Html(TEMPLATES.render("task_action_bar.html", &ctx).unwrap())
+            }
+        },
+    )
+}
+
+pub(crate) async fn get_tag_bar(app_state: State<AppState>) -> Html<String> {
+    let ctx = get_default_context(&app_state);
+    Html(TEMPLATES.render("tag_bar.html", &ctx).unwrap())
+}
+
+pub(crate) async fn just_empty() -> Html<String> {
+    Html(String::new())
+}
+
+pub(crate) async fn display_flash_message(
+    Query(msg): Query<FlashMsg>,
+    app_state: State<AppState>,
+) -> Html<String> {
+    let mut ctx = get_default_context(&app_state);
+    ctx.insert("toast_msg", &msg.msg());
+    ctx.insert("toast_timeout", &msg.timeout());
+    ctx.insert("toast_role", "");
+    Html(TEMPLATES.render("flash_msg.html", &ctx).unwrap())
+}
+
+pub(crate) async fn get_undo_report(app_state: State<AppState>) -> Result<Html<String>, AppError> {
+    let mut ctx = get_default_context(&app_state);
+    match get_undo_history(&app_state.replica).await {
+        Ok(undo_groups) => {
+            let number_operations = undo_groups.first().map_or(0, |p| p.operations.len());
+            let heading = format!("The following {number_operations} operations would be reverted");
+            ctx.insert("heading", &heading);
+            ctx.insert("undo_report", &undo_groups);
+        }
+        Err(e) if is_database_busy(&e) => return Err(e.into()),
+        Err(e) => {
+            ctx.insert("heading", &e.to_string());
+            ctx.insert("undo_report", &Vec::<UndoGroup>::new());
+        }
+    }
+    Ok(Html(TEMPLATES.render("undo_report.html", &ctx).unwrap()))
+}
+
+pub(crate) async fn display_task_add_window(
+    Query(params): Query<TWGlobalState>,
+    app_state: State<AppState>,
+) -> Result<Html<String>, AppError> {
+    let tq: TaskQuery = params
+        .filter_value()
+        .clone()
+        .map_or_else(TaskQuery::default, |v| {
+            if v.is_empty() {
+                TaskQuery::default()
+            } else {
+                serde_json::from_str(&v).unwrap_or_else(|_| TaskQuery::default())
+            }
+        });
+    let project_list = match get_project_list(&app_state.replica).await {
+        Ok(project_list) => project_list,
+        Err(e) if e.is_retryable() => return Err(e),
+        Err(_) => vec![],
+    };
+    let mut ctx = get_default_context(&app_state);
+    let new_task = NewTask::new(
+        None,
+        Some(tq.tags().join(" ")),
+        tq.project().clone(),
+        None,
+        None,
+    );
+    ctx.insert("new_task", &new_task);
+    ctx.insert("tags", &tq.tags().join(" "));
+    ctx.insert("project", tq.project());
+    ctx.insert("project_list", &project_list);
+    ctx.insert("validation", &FormValidation::default());
+    Ok(Html(TEMPLATES.render("task_add.html", &ctx).unwrap()))
+}
+
+pub(crate) async fn undo_last_change(
+    Query(params): Query<TWGlobalState>,
+    Query(undo_params): Query<HashMap<String, String>>,
+    app_state: State<AppState>,
+) -> Html<String> {
+    let steps = undo_params
+        .get("steps")
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let (undone, hooks_failed) = track_hook_failures(task_undo(steps, &app_state)).await;
+    let fm = match undone {
+        Ok(0) => FlashMsg::new("Nothing to undo", None, FlashMsgRoles::Warning),
+        Ok(1) => FlashMsg::new("Undo successful", None, FlashMsgRoles::Success),
+        Ok(reverted) => FlashMsg::new(
+            &format!("Undo successful, reverted {reverted} changes"),
+            None,
+            FlashMsgRoles::Success,
+        ),
+        Err(e) => {
+            error!("Failed to undo: {:?}", e);
+            FlashMsg::new(&e.to_string(), None, FlashMsgRoles::Error)
+        }
+    }
+    .with_hook_failures(hooks_failed);
+    get_tasks_view(&task_query_previous_params(&params), Some(fm), &app_state).await
+}
+
+fn get_tasks_view_data(
+    mut tasks: IndexMap<TaskUUID, crate::backend::task::Task>,
+    filters: &Vec<String>,
+    app_state: &State<AppState>,
+) -> TaskViewDataRetType {
+    let mut tag_map: HashMap<String, String> = HashMap::new();
+    let mut custom_queries_map: HashMap<String, CustomQuery> = HashMap::new();
+    let mut task_shortcut_map: HashMap<String, String> = HashMap::new();
+    let mut shortcuts = HashSet::new();
+    let task_list: Vec<crate::backend::task::Task> = tasks
+        .values_mut()
+        .map(|task| {
+            if let Some(tags) = &mut task.tags {
+                for v in tags.iter_mut() {
+                    if !tasks::is_tag_keyword(v) {
+                        *v = format!("+{v}");
+                    }
+                    let shortcut = make_shortcut_cache(&MnemonicsType::TAG, v, app_state);
+                    tag_map.insert(v.clone(), shortcut);
+                }
+            }
+            if let Some(project) = &task.project {
+                // the project is not in the map, so all of it can be added
+                if !tag_map.contains_key(project) {
+                    let parts: Vec<_> = project.split('.').collect();
+                    let mut total_parts = vec![];
+                    for part in parts {
+                        total_parts.push(part);
+                        let project_name = &total_parts.join(".");
+                        let s = format!("project:{project_name}");
+                        let shortcut =
+                            make_shortcut_cache(&MnemonicsType::PROJECT, project_name, app_state);
+                        tag_map.insert(s, shortcut);
+                    }
+                }
+            }
+            let shortcut = make_shortcut(&mut shortcuts);
+            task_shortcut_map.insert(task.id.unwrap_or(0).to_string(), shortcut);
+            let shortcut = make_shortcut(&mut shortcuts);
+            let uuid = task.uuid.to_string();
+            task_shortcut_map.insert(uuid, shortcut);
+            if let Some(task_annotation) = &mut task.annotations {
+                task_annotation.sort();
+                task_annotation.reverse();
+            }
+            task.clone()
+        })
+        .collect();
+    for filter in filters {
+        if !tag_map.contains_key(filter) {
+            if tasks::is_tag_keyword(filter) {
+            } else if tasks::is_a_tag(filter) {
+                let ky = format!("@{filter}");
+                let shortcut = make_shortcut(&mut shortcuts);
+                tag_map.insert(ky, shortcut);
+            } else {
+                let parts: Vec<_> = filter.split('.').collect();
+                for _part in parts {
+                    let ky = format!("@{filter}");
+                    let shortcut = make_shortcut(&mut shortcuts);
+                    tag_map.insert(ky, shortcut);
+                }
+            }
+        }
+    }
+
+    // prepare custom queries
+    for custom_query in &app_state.app_config.custom_queries {
+        let shortcut = custom_query.1.fixed_key.clone().unwrap_or_else(|| {
+            make_shortcut_cache(&MnemonicsType::CustomQuery, custom_query.0, app_state)
+        });
+        custom_queries_map.insert(shortcut, custom_query.1.clone());
+    }
+
+    TaskViewDataRetType {
+        tasks,
+        task_list,
+        shortcuts,
+        tag_map,
+        task_shortcut_map,
+        custom_queries_map,
+    }
+}
+
+pub(crate) async fn front_page(app_state: State<AppState>) -> Html<String> {
+    let tq = TaskQuery::new(TWGlobalState::default());
+    let tasks = list_tasks(&tq, &app_state).await.unwrap_or_else(|e| {
+        error!("Cannot read task list, error: {:?}", e);
+        let x: IndexMap<TaskUUID, crate::backend::task::Task> = IndexMap::new();
+        x
+    });
+    let filters = tq.as_filter_text();
+    let TaskViewDataRetType {
+        tasks,
+        tag_map,
+        shortcuts: _,
+        task_list,
+        task_shortcut_map,
+        custom_queries_map,
+    } = get_tasks_view_data(tasks, &filters, &app_state);
+    let mut ctx = get_default_context(&app_state);
+    ctx.insert("tasks_db", &tasks);
+    ctx.insert("tasks", &task_list);
+    ctx.insert("current_filter", &tq.as_filter_text());
+    ctx.insert("filter_value", &serde_json::to_string(&tq).unwrap());
+    ctx.insert("tags_map", &tag_map);
+    ctx.insert("custom_queries_map", &custom_queries_map);
+    ctx.insert("task_shortcuts", &task_shortcut_map);
+    ctx.insert("validation", &FormValidation::default());
+    ctx.insert(
+        "STYLESHEET_URL",
+        DIST_CONTENT
+            .get_file("style.css")
+            .unwrap()
+            .contents_utf8()
+            .unwrap(),
+    );
+    ctx.insert(
+        "JS_BUNDLE_PATH",
+        DIST_CONTENT
+            .get_file("bundle.js")
+            .unwrap()
+            .contents_utf8()
+            .unwrap(),
+    );
+    let t: Option<(&TaskUUID, &crate::backend::task::Task)> =
+        tasks.iter().find(|(_, task)| task.start.is_some());
+    if let Some((_, v)) = t {
+        ctx.insert("active_task", v);
+    }
+    Html(TEMPLATES.render("base.html", &ctx).unwrap())
+}
+
+pub(crate) async fn tasks_display(
+    Query(params): Query<TWGlobalState>,
+    app_state: State<AppState>,
+) -> Html<String> {
+    get_tasks_view(&task_query_merge_previous_params(&params), None, &app_state).await
+}
+
+async fn get_tasks_view(
+    tq: &TaskQuery,
+    flash_msg: Option<FlashMsg>,
+    app_state: &State<AppState>,
+) -> Html<String> {
+    Html(get_tasks_view_plain(tq, flash_msg, app_state).await)
+}
+
+async fn get_tasks_view_plain(
+    task_query: &TaskQuery,
+    flash_msg: Option<FlashMsg>,
+    app_state: &State<AppState>,
+) -> String {
+    let mut validation = FormValidation::default();
+    let tasks = match list_tasks(task_query, app_state).await {
+        Ok(t) => t,
+        Err(e) => match e.downcast::<FormValidation>() {
+            Ok(e) => {
+                validation = e;
+                IndexMap::new()
+            }
+            Err(e) => {
+                return e.to_string();
+            }
+        },
+    };
+    let current_filter = task_query.as_filter_text();
+    let mut filter_ar = vec![];
+    for filter in &current_filter {
+        if filter.starts_with("project:") {
+            let mut stack = vec![];
+            for part in filter.split(':').nth(1).unwrap().split('.') {
+                stack.push(part);
+                filter_ar.push(format!("project:{}", stack.join(".")));
+            }
+        } else {
+            filter_ar.push(filter.clone());
+        }
+    }
+    if let Some(custom_query) = task_query.custom_query() {
+        filter_ar.push(format!("custom_query:{custom_query}"));
+    }
+    let TaskViewDataRetType {
+        tasks,
+        tag_map,
+        shortcuts: _,
+        task_list,
+        task_shortcut_map,
+        custom_queries_map,
+    } = get_tasks_view_data(tasks, &filter_ar, app_state);
+    trace!("{:?}", tag_map);
+    let mut ctx_b = get_default_context(app_state);
+    ctx_b.insert("tasks_db", &tasks);
+    ctx_b.insert("tasks", &task_list);
+    ctx_b.insert("current_filter", &filter_ar);
+    ctx_b.insert("filter_value", &serde_json::to_string(&task_query).unwrap());
+    ctx_b.insert("tags_map", &tag_map);
+    ctx_b.insert("custom_queries_map", &custom_queries_map);
+    ctx_b.insert("task_shortcuts", &task_shortcut_map);
+    ctx_b.insert("validation", &validation);
+    if let Some(msg) = flash_msg {
+        msg.to_context(&mut ctx_b);
+    }
+    let t = tasks.iter().find(|(_, task)| task.start.is_some());
+    if let Some((_, v)) = t {
+        ctx_b.insert("active_task", v);
+    }
+    TEMPLATES.render("tasks.html", &ctx_b).unwrap()
+}
+
+pub(crate) async fn create_new_task(
+    app_state: State<AppState>,
+    Form(new_task): Form<NewTask>,
+) -> Response<String> {
+    let task_query = new_task
+        .filter_value()
+        .as_ref()
+        .map_or_else(TaskQuery::default, |tw_q| {
+            serde_json::from_str(tw_q).unwrap()
+        });
+    let (added, hooks_failed) = track_hook_failures(task_add(&new_task, &app_state)).await;
+    match added {
+        Ok(_) => {
+            let flash_msg = FlashMsg::new("New task created", None, FlashMsgRoles::Success)
+                .with_hook_failures(hooks_failed);
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header("HX-Retarget", "#list-of-tasks")
+                .header("HX-Reswap", "innerHTML")
+                .header("Content-Type", "text/html")
+                .body(get_tasks_view_plain(&task_query, Some(flash_msg), &app_state).await)
+                .unwrap()
+        }
+        Err(e) => {
+            let project_list = get_project_list(&app_state.replica)
+                .await
+                .unwrap_or(Vec::new());
+            let mut ctx = get_default_context(&app_state);
+            ctx.insert("new_task", &new_task);
+            ctx.insert("project_list", &project_list);
+            ctx.insert("validation", &e);
+            if hooks_failed {
+                ctx.insert("hook_log_link", HOOK_LOG_PATH);
+            }
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/html")
+                .body(TEMPLATES.render("task_add.html", &ctx).unwrap())
+                .unwrap()
+        }
+    }
+}
+
+pub(crate) async fn do_task_actions(
+    app_state: State<AppState>,
+    Form(multipart): Form<TWGlobalState>,
+) -> Response<String> {
+    info!("{:?}", multipart);
+    let (result, hooks_failed) = track_hook_failures(async {
+        match multipart.action().clone().unwrap() {
+            TaskActions::StatusUpdate => {
+                Ok(task_actions_status_update(&app_state, &multipart).await)
+            }
+            TaskActions::ToggleTimer => Ok(task_actions_toggle_timer(&app_state, &multipart).await),
+            TaskActions::ModifyTask => {
+                error!("Failed: This endpoint is not supported anymore for this task!");
+                Ok(FlashMsg::new(
+                    "Failed to execute command, none provided",
+                    None,
+                    FlashMsgRoles::Error,
+                ))
+            }
+            TaskActions::AnnotateTask | TaskActions::DenotateTask => {
+                task_actions_annotation(&app_state, &multipart).await
+            }
+        }
+    })
+    .await;
+    let fm = match result {
+        Ok(fm) => fm.with_hook_failures(hooks_failed),
+        Err(e) => {
+            return display_task_annotation_error(&app_state, &multipart, &e, hooks_failed).await;
+        }
+    };
+    Response::builder()
+        .status(StatusCode::OK)
+        .body(
+            get_tasks_view_plain(
+                &task_query_previous_params(&multipart),
+                Some(fm),
+                &app_state,
+            )
+            .await,
+        )
+        .unwrap()
+}
+
+async fn task_actions_annotation(
+    app_state: &State<AppState>,
+    multipart: &TWGlobalState,
+) -> Result<FlashMsg, FormValidation> {
+    let task_uuid = multipart
+        .uuid()
+        .ok_or_else(|| FormValidation::with_error("No task given"))?;
+    if matches!(multipart.action(), Some(TaskActions::DenotateTask)) {
+        run_denotate_command(task_uuid, app_state).await?;
+        Ok(FlashMsg::new(
+            "Denotated task",
+            None,
+            FlashMsgRoles::Success,
+        ))
+    } else {
+        let annotation = multipart.task_entry().clone().unwrap_or_default();
+        run_annotate_command(task_uuid, &annotation, app_state).await?;
+        Ok(FlashMsg::new(
+            "Annotation added",
+            None,
+            FlashMsgRoles::Success,
+        ))
+    }
+}
+
+/// Shows the task details again, together with the reason why
+/// the annotation could not be added or removed.
+async fn display_task_annotation_error(
+    app_state: &State<AppState>,
+    multipart: &TWGlobalState,
+    validation: &FormValidation,
+    hooks_failed: bool,
+) -> Response<String> {
+    let task = match multipart.uuid() {
+        Some(task_id) => get_task_details(*task_id, app_state).await.ok(),
+        None => None,
+    };
+    let Some(mut task) = task else {
+        let fm = FlashMsg::new(
+            validation
+                .msg
+                .as_deref()
+                .unwrap_or("Failed to annotate task"),
+            None,
+            FlashMsgRoles::Error,
+        )
+        .with_hook_failures(hooks_failed);
+        return Response::builder()
+            .status(StatusCode::OK)
+            .body(
+                get_tasks_view_plain(&task_query_previous_params(multipart), Some(fm), app_state)
+                    .await,
+            )
+            .unwrap();
+    };
+    let mut ctx = task_details_context(&mut task, app_state).await;
+    ctx.insert("validation", validation);
+    if hooks_failed {
+        ctx.insert("hook_log_link", HOOK_LOG_PATH);
+    }
+    if matches!(multipart.action(), Some(TaskActions::AnnotateTask)) {
+        ctx.insert("task_annotate_cmd", multipart.task_entry());
+    }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("HX-Retarget", "#task-details-modal-box")
+        .header("HX-Reswap", "outerHTML")
+        .header("Content-Type", "text/html")
+        .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
+        .unwrap()
+}
+
+async fn task_actions_toggle_timer(
+    app_state: &State<AppState>,
+    multipart: &TWGlobalState,
+) -> FlashMsg {
+    let task_uuid = (*multipart.uuid()).unwrap();
+    let task_status = multipart
+        .status()
+        .clone()
+        .unwrap_or_else(|| "start".to_string());
+    match toggle_task_active(task_uuid, task_status, app_state).await {
+        Ok(v) => {
+            if v {
+                FlashMsg::new(
+                    &format!("Task {task_uuid} started, any other tasks running were stopped"),
+                    None,
+                    FlashMsgRoles::Success,
+                )
+            } else {
+                FlashMsg::new(
+                    &format!("Task {task_uuid} stopped"),
+                    None,
+                    FlashMsgRoles::Success,
+                )
+            }
+        }
+        Err(e) => {
+            error!("Failed: {}", e);
+            FlashMsg::new(
+                &format!("Failed to update task: {e}"),
+                None,
+                FlashMsgRoles::Error,
+            )
+        }
+    }
+}
+
+async fn task_actions_status_update(
+    app_state: &State<AppState>,
+    multipart: &TWGlobalState,
+) -> FlashMsg {
+    if let Some(task) = crate::from_task_to_task_update(multipart) {
+        match change_task_status(task.clone(), app_state).await {
+            Ok(()) => FlashMsg::new(
+                &format!("Task [{}] was updated", task.uuid),
+                None,
+                FlashMsgRoles::Success,
+            ),
+            Err(e) => {
+                error!("Failed: {}", e);
+                FlashMsg::new(
+                    &format!("Failed to update task: {e}"),
+                    None,
+                    FlashMsgRoles::Error,
+                )
+            }
+        }
+    } else {
+        FlashMsg::new("No task to update", None, FlashMsgRoles::Info)
+    }
+}
+
+pub(crate) async fn update_task_details(
+    Path(task_id): Path<Uuid>,
+    app_state: State<AppState>,
+    Form(multipart): Form<TWGlobalState>,
+) -> Response<String> {
+    let cmd = multipart.task_entry().clone().unwrap();
+    match get_task_details(task_id, &app_state).await {
+        Ok(mut task) => match track_hook_failures(run_modify_command(
+            multipart.uuid().unwrap(),
+            &cmd,
+            &app_state,
+        ))
+        .await
+        {
+            (Ok(()), hooks_failed) => {
+                let flash_msg = FlashMsg::new("Task updated", None, FlashMsgRoles::Success)
+                    .with_hook_failures(hooks_failed);
+                Response::builder()
+                    .status(StatusCode::CREATED)
+                    .header("HX-Retarget", "#list-of-tasks")
+                    .header("HX-Reswap", "innerHTML")
+                    .header("Content-Type", "text/html")
+                    .body(
+                        get_tasks_view_plain(
+                            &task_query_previous_params(&multipart),
+                            Some(flash_msg),
+                            &app_state,
+                        )
+                        .await,
+                    )
+                    .unwrap()
+            }
+            (Err(e), hooks_failed) => {
+                let mut ctx = task_details_context(&mut task, &app_state).await;
+                ctx.insert("validation", &e);
+                ctx.insert("task_edit_cmd", &cmd);
+                if hooks_failed {
+                    ctx.insert("hook_log_link", HOOK_LOG_PATH);
+                }
+                Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "text/html")
+                    .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
+                    .unwrap()
+            }
+        },
+        Err(_) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(String::new())
+            .unwrap(),
+    }
+}
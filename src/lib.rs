@@ -19,6 +19,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
+use crate::endpoints::hooks::HOOK_LOG_PATH;
 use crate::endpoints::tasks::task_query_builder::{TaskQuery, TaskReport};
 use crate::endpoints::tasks::{is_a_tag, is_tag_keyword};
 use chrono::{DateTime, TimeDelta};
@@ -30,7 +31,6 @@ use taskchampion::Uuid;
 use tera::{Context, escape_html};
 use tracing::{trace, warn};
 
-
 pub static DIST_CONTENT: include_dir::Dir = include_dir!("dist");
 
 pub fn load_templates_from_include_dir() -> anyhow::Result<Vec<(String, String)>> {
@@ -98,6 +98,8 @@ pub struct FlashMsg {
     msg: String,
     timeout: Option<u64>,
     role: FlashMsgRoles,
+    /// Opens the hook log from the message.
+    link: Option<String>,
 }
 
 impl FlashMsg {
@@ -122,7 +124,17 @@ impl FlashMsg {
             msg: msg.to_string(),
             timeout,
             role,
+            link: None,
+        }
+    }
+
+    /// Links the hook log, if one of the hooks of the action failed.
+    #[must_use]
+    pub fn with_hook_failures(mut self, hooks_failed: bool) -> Self {
+        if hooks_failed {
+            self.link = Some(HOOK_LOG_PATH.to_string());
         }
+        self
     }
 
     pub fn to_context(&self, ctx: &mut Context) {
@@ -130,6 +142,9 @@ impl FlashMsg {
         ctx.insert("toast_msg", &self.msg());
         ctx.insert("toast_role", &self.role());
         ctx.insert("toast_timeout", &self.timeout());
+        if let Some(link) = &self.link {
+            ctx.insert("toast_link", link);
+        }
     }
 }
 
@@ -451,13 +466,19 @@ fn get_date() -> impl tera::Function {
     )
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, utoipa::ToSchema)]
 pub struct NewTask {
     description: String,
+    /// Tags like `+home +garden`.
     tags: Option<String>,
     project: Option<String>,
+    /// Filter of the list the task was added from, used by the browser.
     filter_value: Option<String>,
+    /// Further attributes like `due:tomorrow priority:H`.
     additional: Option<String>,
+    /// Date expressions like `monday` or `eom`.
+    scheduled: Option<String>,
+    until: Option<String>,
 }
 
 impl NewTask {
@@ -474,6 +495,8 @@ impl NewTask {
             project,
             filter_value,
             additional,
+            scheduled: None,
+            until: None,
         }
     }
     pub fn description(&self) -> &str {
@@ -491,6 +514,12 @@ impl NewTask {
     pub fn additional(&self) -> &Option<String> {
         &self.additional
     }
+    pub fn scheduled(&self) -> &Option<String> {
+        &self.scheduled
+    }
+    pub fn until(&self) -> &Option<String> {
+        &self.until
+    }
 }
 
 fn get_timer() -> impl tera::Function {
@@ -531,8 +560,14 @@ fn get_random_appstate() -> (TempDir, AppState) {
     let tmp_dir = tempdir().expect("Cannot create a tempdir.");
     let app_state = AppState {
         task_storage_path: tmp_dir.path().to_path_buf(),
+        replica: backend::replica::ReplicaPool::new(tmp_dir.path().to_path_buf()),
         // don't want any user hooks to execute accidentally
         task_hooks_path: Some(tmp_dir.path().join("hooks")),
+        sync_server: backend::sync_server::SyncServerStorage::new(
+            tmp_dir.path().join("sync-server"),
+            100,
+        ),
+        api_tokens: backend::api_tokens::ApiTokenStore::new(tmp_dir.path().join("api_tokens.json")),
         ..AppState::default()
     };
     (tmp_dir, app_state)
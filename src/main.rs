@@ -8,36 +8,19 @@
  * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  */
 
-use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
-use axum::response::{Html, Response};
-use axum::routing::post;
-use axum::{Form, Router, routing::get};
-use indexmap::IndexMap;
+use axum::Router;
 use listenfd::ListenFd;
-use std::collections::{HashMap, HashSet};
 use std::env;
-use std::string::ToString;
-use taskchampion::Uuid;
-use taskwarrior_web::backend::task::{TaskOperation, get_project_list};
-use taskwarrior_web::core::app::{AppState, get_default_context};
-use taskwarrior_web::core::cache::MnemonicsType;
-use taskwarrior_web::core::config::CustomQuery;
-use taskwarrior_web::core::errors::FormValidation;
-use taskwarrior_web::core::utils::{make_shortcut, make_shortcut_cache};
-use taskwarrior_web::endpoints::tasks::task_query_builder::TaskQuery;
-use taskwarrior_web::endpoints::tasks::{self, change_task_status, display_task_details};
-use taskwarrior_web::endpoints::tasks::{
-    TaskUUID, TaskViewDataRetType, fetch_active_task, list_tasks, run_annotate_command,
-    run_denotate_command, run_modify_command, task_add, task_undo, toggle_task_active,
-};
-use taskwarrior_web::endpoints::tasks::{
-    api_denotate_task_entry, display_task_delete, get_task_details, get_task_details_form,
-};
-use taskwarrior_web::{FlashMsg, FlashMsgRoles, NewTask, TEMPLATES, TWGlobalState, TaskActions, task_query_merge_previous_params, task_query_previous_params, DIST_CONTENT};
+use taskwarrior_web::backend::auth::hash_password;
+use taskwarrior_web::backend::hooks::{TaskEvent, run_event_hooks};
+use taskwarrior_web::backend::sync::spawn_sync_scheduler;
+use taskwarrior_web::backend::watcher::spawn_task_db_watcher;
+use taskwarrior_web::backend::webhooks::spawn_webhook_worker;
+use taskwarrior_web::core::app::AppState;
+use taskwarrior_web::endpoints;
 use tokio::net::TcpListener;
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
-use tracing::{Level, error, info, trace};
+use tracing::{Level, error, info};
 use tracing_subscriber::layer::SubscriberExt;
 
 use tracing_subscriber::util::SubscriberInitExt;
@@ -64,6 +47,9 @@ async fn reload_listener(app: Router) -> anyhow::Result<()> {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    if env::args().nth(1).as_deref() == Some("hash-password") {
+        return print_password_hash();
+    }
     // initialize tracing
     init_tracing();
     if dotenvy::dotenv().is_err() {
@@ -71,40 +57,40 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let app_settings = AppState::default();
+    // like Taskwarrior, a rejecting on-launch hook stops the start.
+    run_event_hooks(&app_settings, &TaskEvent::OnLaunch, &[]).await?;
+    spawn_sync_scheduler(&app_settings);
+    spawn_webhook_worker(&app_settings);
+    if let Err(e) = spawn_task_db_watcher(&app_settings) {
+        error!(
+            "Live updates are disabled, cannot watch the task database: {:?}",
+            e
+        );
+    }
 
     // build our application with a route
-    let app = Router::new()
-        .route("/", get(front_page))
-        .nest_service("/dist", tower_http::services::ServeDir::new("./dist"))
-        .route("/tasks", get(tasks_display))
-        .route("/tasks", post(do_task_actions))
-        .route("/tasks/undo/report", get(get_undo_report))
-        .route("/tasks/undo/confirmed", post(undo_last_change))
-        .route("/tasks/add", get(display_task_add_window))
-        .route("/tasks/active", get(get_active_task))
-        .route("/tasks/add", post(create_new_task))
-        .route("/tasks/{id}/details", get(display_task_details))
-        .route("/tasks/{id}/details", post(update_task_details))
-        .route("/tasks/{id}/delete", get(display_task_delete))
-        .route("/tasks/{id}/denotate", post(api_denotate_task_entry))
-        .route("/msg", get(display_flash_message))
-        .route("/msg_clr", get(just_empty))
-        .route("/tag_bar", get(get_tag_bar))
-        .route("/task_action_bar", get(get_task_action_bar))
-        .route("/bars", get(get_bar))
-        .route("/sync", get(check_and_sync))
-        .with_state(app_settings)
-        .layer(
-            TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
-                .on_response(DefaultOnResponse::new().level(Level::INFO)),
-        );
+    let app = endpoints::app(app_settings).layer(
+        TraceLayer::new_for_http()
+            .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+            .on_response(DefaultOnResponse::new().level(Level::INFO)),
+    );
 
     // run our app with hyper, listening globally on port 3000
     reload_listener(app).await?;
     Ok(())
 }
 
+/// Reads a password from stdin and prints its hash for `auth.users`.
+fn print_password_hash() -> anyhow::Result<()> {
+    let mut password = String::new();
+    std::io::stdin().read_line(&mut password)?;
+    println!(
+        "{}",
+        hash_password(password.trim_end_matches(['\r', '\n']))?
+    );
+    Ok(())
+}
+
 fn init_tracing() {
     tracing_subscriber::registry()
         .with(
@@ -114,519 +100,3 @@ fn init_tracing() {
         .with(tracing_subscriber::fmt::layer().with_line_number(true))
         .init();
 }
-
-async fn get_active_task(app_state: State<AppState>) -> Html<String> {
-    let mut ctx = get_default_context(&app_state);
-    if let Ok(Some(v)) = fetch_active_task() {
-        ctx.insert("active_task", &v);
-    }
-    Html(TEMPLATES.render("active_task.html", &ctx).unwrap())
-}
-
-async fn get_task_action_bar(app_state: State<AppState>) -> Html<String> {
-    let ctx = get_default_context(&app_state);
-    Html(TEMPLATES.render("task_action_bar.html", &ctx).unwrap())
-}
-
-async fn check_and_sync(app_state: State<AppState>) -> Html<String> {
-    // will perform a sync operation
-    if app_state.sync_interval > 0 {
-        // TODO: check sync status
-
-        // TODO: Show update available message, need a direct keyboard shortcut to perform update
-        return Html("Task Update available".to_string());
-    }
-    Html(String::new())
-}
-
-async fn get_bar(
-    Query(param): Query<HashMap<String, String>>,
-    app_state: State<AppState>,
-) -> Html<String> {
-    param.get("bar").map_or_else(
-        || Html(String::new()),
-        |bar| {
-            let ctx = get_default_context(&app_state);
-            if bar == "left_action_bar" {
-                Html(TEMPLATES.render("left_action_bar.html", &ctx).unwrap())
-            } else {
-                Html(TEMPLATES.render("task_action_bar.html", &ctx).unwrap())
-            }
-        },
-    )
-}
-
-async fn get_tag_bar(app_state: State<AppState>) -> Html<String> {
-    let ctx = get_default_context(&app_state);
-    Html(TEMPLATES.render("tag_bar.html", &ctx).unwrap())
-}
-
-async fn just_empty() -> Html<String> {
-    Html(String::new())
-}
-
-async fn display_flash_message(
-    Query(msg): Query<FlashMsg>,
-    app_state: State<AppState>,
-) -> Html<String> {
-    let mut ctx = get_default_context(&app_state);
-    ctx.insert("toast_msg", &msg.msg());
-    ctx.insert("toast_timeout", &msg.timeout());
-    ctx.insert("toast_role", "");
-    Html(TEMPLATES.render("flash_msg.html", &ctx).unwrap())
-}
-
-async fn get_undo_report(app_state: State<AppState>) -> Html<String> {
-    match taskwarrior_web::backend::task::get_undo_operations(&app_state.task_storage_path).await {
-        Ok(task_operations) => {
-            let mut ctx = get_default_context(&app_state);
-            let number_operations = task_operations
-                .values()
-                .map(std::vec::Vec::len)
-                .sum::<usize>();
-            let heading = format!("The following {number_operations} operations would be reverted");
-            ctx.insert("heading", &heading);
-            ctx.insert("undo_report", &task_operations);
-            Html(TEMPLATES.render("undo_report.html", &ctx).unwrap())
-        }
-        Err(e) => {
-            let mut ctx = get_default_context(&app_state);
-            ctx.insert("heading", &e.to_string());
-            ctx.insert("undo_report", &HashMap::<Uuid, Vec<TaskOperation>>::new());
-            Html(TEMPLATES.render("error.html", &ctx).unwrap())
-        }
-    }
-}
-
-async fn display_task_add_window(
-    Query(params): Query<TWGlobalState>,
-    app_state: State<AppState>,
-) -> Html<String> {
-    let tq: TaskQuery = params
-        .filter_value()
-        .clone()
-        .map_or_else(TaskQuery::default, |v| {
-            if v.is_empty() {
-                TaskQuery::default()
-            } else {
-                serde_json::from_str(&v).unwrap_or_else(|_| TaskQuery::default())
-            }
-        });
-    let project_list = get_project_list(&app_state.task_storage_path)
-        .await
-        .unwrap_or_default();
-    let mut ctx = get_default_context(&app_state);
-    let new_task = NewTask::new(
-        None,
-        Some(tq.tags().join(" ")),
-        tq.project().clone(),
-        None,
-        None,
-    );
-    ctx.insert("new_task", &new_task);
-    ctx.insert("tags", &tq.tags().join(" "));
-    ctx.insert("project", tq.project());
-    ctx.insert("project_list", &project_list);
-    ctx.insert("validation", &FormValidation::default());
-    Html(TEMPLATES.render("task_add.html", &ctx).unwrap())
-}
-
-async fn undo_last_change(
-    Query(params): Query<TWGlobalState>,
-    app_state: State<AppState>,
-) -> Html<String> {
-    task_undo().unwrap();
-    let fm = FlashMsg::new("Undo successful", None, FlashMsgRoles::Success);
-    get_tasks_view(&task_query_previous_params(&params), Some(fm), &app_state)
-}
-
-fn get_tasks_view_data(
-    mut tasks: IndexMap<TaskUUID, taskwarrior_web::backend::task::Task>,
-    filters: &Vec<String>,
-    app_state: &State<AppState>,
-) -> TaskViewDataRetType {
-    let mut tag_map: HashMap<String, String> = HashMap::new();
-    let mut custom_queries_map: HashMap<String, CustomQuery> = HashMap::new();
-    let mut task_shortcut_map: HashMap<String, String> = HashMap::new();
-    let mut shortcuts = HashSet::new();
-    let task_list: Vec<taskwarrior_web::backend::task::Task> = tasks
-        .values_mut()
-        .map(|task| {
-            if let Some(tags) = &mut task.tags {
-                for v in tags.iter_mut() {
-                    if !tasks::is_tag_keyword(v) {
-                        *v = format!("+{v}");
-                    }
-                    let shortcut = make_shortcut_cache(&MnemonicsType::TAG, v, app_state);
-                    tag_map.insert(v.clone(), shortcut);
-                }
-            }
-            if let Some(project) = &task.project {
-                // the project is not in the map, so all of it can be added
-                if !tag_map.contains_key(project) {
-                    let parts: Vec<_> = project.split('.').collect();
-                    let mut total_parts = vec![];
-                    for part in parts {
-                        total_parts.push(part);
-                        let project_name = &total_parts.join(".");
-                        let s = format!("project:{project_name}");
-                        let shortcut =
-                            make_shortcut_cache(&MnemonicsType::PROJECT, project_name, app_state);
-                        tag_map.insert(s, shortcut);
-                    }
-                }
-            }
-            let shortcut = make_shortcut(&mut shortcuts);
-            task_shortcut_map.insert(task.id.unwrap_or(0).to_string(), shortcut);
-            let shortcut = make_shortcut(&mut shortcuts);
-            let uuid = task.uuid.to_string();
-            task_shortcut_map.insert(uuid, shortcut);
-            if let Some(task_annotation) = &mut task.annotations {
-                task_annotation.sort();
-                task_annotation.reverse();
-            }
-            task.clone()
-        })
-        .collect();
-    for filter in filters {
-        if !tag_map.contains_key(filter) {
-            if tasks::is_tag_keyword(filter) {
-            } else if tasks::is_a_tag(filter) {
-                let ky = format!("@{filter}");
-                let shortcut = make_shortcut(&mut shortcuts);
-                tag_map.insert(ky, shortcut);
-            } else {
-                let parts: Vec<_> = filter.split('.').collect();
-                for _part in parts {
-                    let ky = format!("@{filter}");
-                    let shortcut = make_shortcut(&mut shortcuts);
-                    tag_map.insert(ky, shortcut);
-                }
-            }
-        }
-    }
-
-    // prepare custom queries
-    for custom_query in &app_state.app_config.custom_queries {
-        let shortcut = custom_query.1.fixed_key.clone().unwrap_or_else(|| {
-            make_shortcut_cache(&MnemonicsType::CustomQuery, custom_query.0, app_state)
-        });
-        custom_queries_map.insert(shortcut, custom_query.1.clone());
-    }
-
-    TaskViewDataRetType {
-        tasks,
-        task_list,
-        shortcuts,
-        tag_map,
-        task_shortcut_map,
-        custom_queries_map,
-    }
-}
-
-async fn front_page(app_state: State<AppState>) -> Html<String> {
-    let tq = TaskQuery::new(TWGlobalState::default());
-    let tasks = list_tasks(&tq).unwrap_or_else(|e| {
-        error!("Cannot read task list, error: {:?}", e);
-        let x: IndexMap<TaskUUID, taskwarrior_web::backend::task::Task> = IndexMap::new();
-        x
-    });
-    let filters = tq.as_filter_text();
-    let TaskViewDataRetType {
-        tasks,
-        tag_map,
-        shortcuts: _,
-        task_list,
-        task_shortcut_map,
-        custom_queries_map,
-    } = get_tasks_view_data(tasks, &filters, &app_state);
-    let mut ctx = get_default_context(&app_state);
-    ctx.insert("tasks_db", &tasks);
-    ctx.insert("tasks", &task_list);
-    ctx.insert("current_filter", &tq.as_filter_text());
-    ctx.insert("filter_value", &serde_json::to_string(&tq).unwrap());
-    ctx.insert("tags_map", &tag_map);
-    ctx.insert("custom_queries_map", &custom_queries_map);
-    ctx.insert("task_shortcuts", &task_shortcut_map);
-    ctx.insert("STYLESHEET_URL", DIST_CONTENT.get_file("style.css").unwrap().contents_utf8().unwrap());
-    ctx.insert("JS_BUNDLE_PATH", DIST_CONTENT.get_file("bundle.js").unwrap().contents_utf8().unwrap());
-    let t: Option<(&TaskUUID, &taskwarrior_web::backend::task::Task)> =
-        tasks.iter().find(|(_, task)| task.start.is_some());
-    if let Some((_, v)) = t {
-        ctx.insert("active_task", v);
-    }
-    Html(TEMPLATES.render("base.html", &ctx).unwrap())
-}
-
-async fn tasks_display(
-    Query(params): Query<TWGlobalState>,
-    app_state: State<AppState>,
-) -> Html<String> {
-    get_tasks_view(&task_query_merge_previous_params(&params), None, &app_state)
-}
-
-fn get_tasks_view(
-    tq: &TaskQuery,
-    flash_msg: Option<FlashMsg>,
-    app_state: &State<AppState>,
-) -> Html<String> {
-    Html(get_tasks_view_plain(tq, flash_msg, app_state))
-}
-
-fn get_tasks_view_plain(
-    task_query: &TaskQuery,
-    flash_msg: Option<FlashMsg>,
-    app_state: &State<AppState>,
-) -> String {
-    let tasks = match list_tasks(task_query) {
-        Ok(t) => t,
-        Err(e) => {
-            return e.to_string();
-        }
-    };
-    let current_filter = task_query.as_filter_text();
-    let mut filter_ar = vec![];
-    for filter in &current_filter {
-        if filter.starts_with("project:") {
-            let mut stack = vec![];
-            for part in filter.split(':').nth(1).unwrap().split('.') {
-                stack.push(part);
-                filter_ar.push(format!("project:{}", stack.join(".")));
-            }
-        } else {
-            filter_ar.push(filter.clone());
-        }
-    }
-    if let Some(custom_query) = task_query.custom_query() {
-        filter_ar.push(format!("custom_query:{custom_query}"));
-    }
-    let TaskViewDataRetType {
-        tasks,
-        tag_map,
-        shortcuts: _,
-        task_list,
-        task_shortcut_map,
-        custom_queries_map,
-    } = get_tasks_view_data(tasks, &filter_ar, app_state);
-    trace!("{:?}", tag_map);
-    let mut ctx_b = get_default_context(app_state);
-    ctx_b.insert("tasks_db", &tasks);
-    ctx_b.insert("tasks", &task_list);
-    ctx_b.insert("current_filter", &filter_ar);
-    ctx_b.insert("filter_value", &serde_json::to_string(&task_query).unwrap());
-    ctx_b.insert("tags_map", &tag_map);
-    ctx_b.insert("custom_queries_map", &custom_queries_map);
-    ctx_b.insert("task_shortcuts", &task_shortcut_map);
-    if let Some(msg) = flash_msg {
-        msg.to_context(&mut ctx_b);
-    }
-    let t = tasks.iter().find(|(_, task)| task.start.is_some());
-    if let Some((_, v)) = t {
-        ctx_b.insert("active_task", v);
-    }
-    TEMPLATES.render("tasks.html", &ctx_b).unwrap()
-}
-
-async fn create_new_task(
-    app_state: State<AppState>,
-    Form(new_task): Form<NewTask>,
-) -> Response<String> {
-    let task_query = new_task
-        .filter_value()
-        .as_ref()
-        .map_or_else(TaskQuery::default, |tw_q| {
-            serde_json::from_str(tw_q).unwrap()
-        });
-    match task_add(&new_task, &app_state).await {
-        Ok(_) => {
-            let flash_msg = FlashMsg::new("New task created", None, FlashMsgRoles::Success);
-            Response::builder()
-                .status(StatusCode::CREATED)
-                .header("HX-Retarget", "#list-of-tasks")
-                .header("HX-Reswap", "innerHTML")
-                .header("Content-Type", "text/html")
-                .body(get_tasks_view_plain(
-                    &task_query,
-                    Some(flash_msg),
-                    &app_state,
-                ))
-                .unwrap()
-        }
-        Err(e) => {
-            let project_list = get_project_list(&app_state.task_storage_path)
-                .await
-                .unwrap_or(Vec::new());
-            let mut ctx = get_default_context(&app_state);
-            ctx.insert("new_task", &new_task);
-            ctx.insert("project_list", &project_list);
-            ctx.insert("validation", &e);
-            Response::builder()
-                .status(StatusCode::OK)
-                .header("Content-Type", "text/html")
-                .body(TEMPLATES.render("task_add.html", &ctx).unwrap())
-                .unwrap()
-        }
-    }
-}
-
-async fn do_task_actions(
-    app_state: State<AppState>,
-    Form(multipart): Form<TWGlobalState>,
-) -> Response<String> {
-    info!("{:?}", multipart);
-    let fm = match multipart.action().clone().unwrap() {
-        TaskActions::StatusUpdate => task_actions_status_update(&app_state, &multipart).await,
-        TaskActions::ToggleTimer => task_actions_toggle_timer(&app_state, &multipart).await,
-        TaskActions::ModifyTask => {
-            error!("Failed: This endpoint is not supported anymore for this task!");
-            FlashMsg::new(
-                "Failed to execute command, none provided",
-                None,
-                FlashMsgRoles::Error,
-            )
-        }
-        TaskActions::AnnotateTask => task_actions_annotate_task(&multipart),
-        TaskActions::DenotateTask => task_actions_denotate_task(&multipart),
-    };
-    Response::builder()
-        .status(StatusCode::OK)
-        .body(get_tasks_view_plain(
-            &task_query_previous_params(&multipart),
-            Some(fm),
-            &app_state,
-        ))
-        .unwrap()
-}
-
-fn task_actions_denotate_task(multipart: &TWGlobalState) -> FlashMsg {
-    match run_denotate_command(multipart.uuid().unwrap()) {
-        Ok(()) => FlashMsg::new("Denotated task", None, FlashMsgRoles::Success),
-        Err(e) => FlashMsg::new(
-            &format!("Denotation command failed: {e}"),
-            None,
-            FlashMsgRoles::Error,
-        ),
-    }
-}
-
-fn task_actions_annotate_task(multipart: &TWGlobalState) -> FlashMsg {
-    let cmd = multipart.task_entry().clone().unwrap();
-    if cmd.is_empty() {
-        error!("Failed: No command provided");
-        FlashMsg::new(
-            "Failed to execute command, none provided",
-            None,
-            FlashMsgRoles::Error,
-        )
-    } else {
-        match run_annotate_command(multipart.uuid().unwrap(), &cmd) {
-            Ok(()) => FlashMsg::new("Annotation added", None, FlashMsgRoles::Success),
-            Err(e) => FlashMsg::new(
-                &format!("Annotation command failed: {e}"),
-                None,
-                FlashMsgRoles::Error,
-            ),
-        }
-    }
-}
-
-async fn task_actions_toggle_timer(app_state: &State<AppState>, multipart: &TWGlobalState) -> FlashMsg {
-    let task_uuid = (*multipart.uuid()).unwrap();
-    let task_status = multipart
-        .status()
-        .clone()
-        .unwrap_or_else(|| "start".to_string());
-    match toggle_task_active(task_uuid, task_status, app_state).await {
-        Ok(v) => {
-            if v {
-                FlashMsg::new(
-                    &format!(
-                        "Task {task_uuid} started, any other tasks running were stopped"
-                    ),
-                    None,
-                    FlashMsgRoles::Success,
-                )
-            } else {
-                FlashMsg::new(
-                    &format!("Task {task_uuid} stopped"),
-                    None,
-                    FlashMsgRoles::Success,
-                )
-            }
-        }
-        Err(e) => {
-            error!("Failed: {}", e);
-            FlashMsg::new(
-                &format!("Failed to update task: {e}"),
-                None,
-                FlashMsgRoles::Error,
-            )
-        }
-    }
-}
-
-async fn task_actions_status_update(app_state: &State<AppState>, multipart: &TWGlobalState) -> FlashMsg {
-    if let Some(task) = taskwarrior_web::from_task_to_task_update(multipart) {
-        match change_task_status(task.clone(), app_state).await {
-            Ok(()) => FlashMsg::new(
-                &format!("Task [{}] was updated", task.uuid),
-                None,
-                FlashMsgRoles::Success,
-            ),
-            Err(e) => {
-                error!("Failed: {}", e);
-                FlashMsg::new(
-                    &format!("Failed to update task: {e}"),
-                    None,
-                    FlashMsgRoles::Error,
-                )
-            }
-        }
-    } else {
-        FlashMsg::new("No task to update", None, FlashMsgRoles::Info)
-    }
-}
-
-async fn update_task_details(
-    Path(task_id): Path<Uuid>,
-    app_state: State<AppState>,
-    Form(multipart): Form<TWGlobalState>,
-) -> Response<String> {
-    let cmd = multipart.task_entry().clone().unwrap();
-    match get_task_details(task_id.to_string()) {
-        Ok(mut task) => match run_modify_command(multipart.uuid().unwrap(), &cmd, &app_state).await
-        {
-            Ok(()) => {
-                let flash_msg = FlashMsg::new("Task updated", None, FlashMsgRoles::Success);
-                Response::builder()
-                    .status(StatusCode::CREATED)
-                    .header("HX-Retarget", "#list-of-tasks")
-                    .header("HX-Reswap", "innerHTML")
-                    .header("Content-Type", "text/html")
-                    .body(get_tasks_view_plain(
-                        &task_query_previous_params(&multipart),
-                        Some(flash_msg),
-                        &app_state,
-                    ))
-                    .unwrap()
-            }
-            Err(e) => {
-                let tasks_deps = get_task_details_form(&mut task, &app_state).await;
-                let mut ctx = get_default_context(&app_state);
-                ctx.insert("tasks_db", &tasks_deps);
-                ctx.insert("task", &task);
-                ctx.insert("validation", &e);
-                ctx.insert("task_edit_cmd", &cmd);
-                Response::builder()
-                    .status(StatusCode::OK)
-                    .header("Content-Type", "text/html")
-                    .body(TEMPLATES.render("task_details.html", &ctx).unwrap())
-                    .unwrap()
-            }
-        },
-        Err(_) => Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body(String::new())
-            .unwrap(),
-    }
-}